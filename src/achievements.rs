@@ -0,0 +1,299 @@
+use chrono::{DateTime, Days, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::finger_map::Finger;
+use crate::goals::{DailyGoal, StreakData};
+use crate::modes::{Mode, PassageLength};
+use crate::stats::{ProgressData, TestResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Achievement {
+    FirstTest,
+    Wpm40,
+    Wpm60,
+    Wpm80,
+    Wpm100,
+    Tests10,
+    Tests100,
+    Tests500,
+    HourPracticed,
+    PerfectLong,
+    AllDrills40,
+    Streak3,
+    Streak7,
+    Streak30,
+}
+
+impl Achievement {
+    pub fn all() -> Vec<Achievement> {
+        vec![
+            Achievement::FirstTest,
+            Achievement::Wpm40,
+            Achievement::Wpm60,
+            Achievement::Wpm80,
+            Achievement::Wpm100,
+            Achievement::Tests10,
+            Achievement::Tests100,
+            Achievement::Tests500,
+            Achievement::HourPracticed,
+            Achievement::PerfectLong,
+            Achievement::AllDrills40,
+            Achievement::Streak3,
+            Achievement::Streak7,
+            Achievement::Streak30,
+        ]
+    }
+
+    /// Stable identifier used when persisting unlocks
+    pub fn id(&self) -> &'static str {
+        match self {
+            Achievement::FirstTest => "first_test",
+            Achievement::Wpm40 => "wpm_40",
+            Achievement::Wpm60 => "wpm_60",
+            Achievement::Wpm80 => "wpm_80",
+            Achievement::Wpm100 => "wpm_100",
+            Achievement::Tests10 => "tests_10",
+            Achievement::Tests100 => "tests_100",
+            Achievement::Tests500 => "tests_500",
+            Achievement::HourPracticed => "hour_practiced",
+            Achievement::PerfectLong => "perfect_long",
+            Achievement::AllDrills40 => "all_drills_40",
+            Achievement::Streak3 => "streak_3",
+            Achievement::Streak7 => "streak_7",
+            Achievement::Streak30 => "streak_30",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Achievement::FirstTest => "First Steps",
+            Achievement::Wpm40 => "Getting Up to Speed",
+            Achievement::Wpm60 => "Sixty Club",
+            Achievement::Wpm80 => "Fast Fingers",
+            Achievement::Wpm100 => "Triple Digits",
+            Achievement::Tests10 => "Warming Up",
+            Achievement::Tests100 => "Centurion",
+            Achievement::Tests500 => "Dedicated",
+            Achievement::HourPracticed => "Time Well Spent",
+            Achievement::PerfectLong => "Flawless",
+            Achievement::AllDrills40 => "Every Finger Trained",
+            Achievement::Streak3 => "Habit Forming",
+            Achievement::Streak7 => "Week Warrior",
+            Achievement::Streak30 => "Unstoppable",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Achievement::FirstTest => "Complete your first test",
+            Achievement::Wpm40 => "Reach 40 WPM",
+            Achievement::Wpm60 => "Reach 60 WPM",
+            Achievement::Wpm80 => "Reach 80 WPM",
+            Achievement::Wpm100 => "Reach 100 WPM",
+            Achievement::Tests10 => "Complete 10 tests",
+            Achievement::Tests100 => "Complete 100 tests",
+            Achievement::Tests500 => "Complete 500 tests",
+            Achievement::HourPracticed => "Practice for a total of one hour",
+            Achievement::PerfectLong => "Finish a long passage with 100% accuracy",
+            Achievement::AllDrills40 => "Pass every finger drill above 40 WPM",
+            Achievement::Streak3 => "Meet your daily goal 3 days in a row",
+            Achievement::Streak7 => "Meet your daily goal 7 days in a row",
+            Achievement::Streak30 => "Meet your daily goal 30 days in a row",
+        }
+    }
+
+    fn is_earned(&self, progress: &ProgressData, streaks: &StreakData) -> bool {
+        let results = &progress.results;
+        match self {
+            Achievement::FirstTest => !results.is_empty(),
+            Achievement::Wpm40 => progress.best_wpm() >= 40.0,
+            Achievement::Wpm60 => progress.best_wpm() >= 60.0,
+            Achievement::Wpm80 => progress.best_wpm() >= 80.0,
+            Achievement::Wpm100 => progress.best_wpm() >= 100.0,
+            Achievement::Tests10 => results.len() >= 10,
+            Achievement::Tests100 => results.len() >= 100,
+            Achievement::Tests500 => results.len() >= 500,
+            Achievement::HourPracticed => progress.total_practice_secs() >= 3600,
+            Achievement::PerfectLong => results
+                .iter()
+                .any(|r| r.length == Some(PassageLength::Long) && r.accuracy >= 100.0),
+            Achievement::AllDrills40 => Finger::all().into_iter().all(|finger| {
+                results
+                    .iter()
+                    .any(|r| r.mode == Some(Mode::FingerDrill(finger)) && r.wpm >= 40.0)
+            }),
+            Achievement::Streak3 => streaks.longest >= 3,
+            Achievement::Streak7 => streaks.longest >= 7,
+            Achievement::Streak30 => streaks.longest >= 30,
+        }
+    }
+
+    /// When the achievement was earned: the time of the result that earned
+    /// it, or `None` when that can't be worked out from the history
+    fn earned_at(&self, progress: &ProgressData, streaks: &StreakData, goal: &DailyGoal) -> Option<DateTime<Utc>> {
+        let mut results: Vec<&TestResult> = progress.results.iter().collect();
+        results.sort_by_key(|r| r.timestamp);
+        let first = |earns: &dyn Fn(&TestResult) -> bool| results.iter().find(|r| earns(r)).map(|r| r.timestamp);
+        let nth = |n: usize| results.get(n - 1).map(|r| r.timestamp);
+
+        match self {
+            Achievement::FirstTest => nth(1),
+            Achievement::Wpm40 => first(&|r| r.wpm >= 40.0),
+            Achievement::Wpm60 => first(&|r| r.wpm >= 60.0),
+            Achievement::Wpm80 => first(&|r| r.wpm >= 80.0),
+            Achievement::Wpm100 => first(&|r| r.wpm >= 100.0),
+            Achievement::Tests10 => nth(10),
+            Achievement::Tests100 => nth(100),
+            Achievement::Tests500 => nth(500),
+            Achievement::HourPracticed => results
+                .iter()
+                .scan(0, |secs, r| {
+                    *secs += r.duration_secs;
+                    Some((*secs, r.timestamp))
+                })
+                .find(|&(secs, _)| secs >= 3600)
+                .map(|(_, timestamp)| timestamp),
+            Achievement::PerfectLong => first(&|r| r.length == Some(PassageLength::Long) && r.accuracy >= 100.0),
+            Achievement::AllDrills40 => Finger::all()
+                .into_iter()
+                .map(|finger| first(&|r| r.mode == Some(Mode::FingerDrill(finger)) && r.wpm >= 40.0))
+                .collect::<Option<Vec<_>>>()?
+                .into_iter()
+                .max(),
+            Achievement::Streak3 => streak_reached(3, &results, streaks, goal),
+            Achievement::Streak7 => streak_reached(7, &results, streaks, goal),
+            Achievement::Streak30 => streak_reached(30, &results, streaks, goal),
+        }
+    }
+}
+
+/// The result that met the goal on the day the current streak reached
+/// `days`. Earlier streaks leave no record of their days.
+fn streak_reached(
+    days: u32,
+    results: &[&TestResult],
+    streaks: &StreakData,
+    goal: &DailyGoal,
+) -> Option<DateTime<Utc>> {
+    if streaks.current < days {
+        return None;
+    }
+    let day = streaks.last_day?.checked_sub_days(Days::new((streaks.current - days) as u64))?;
+    let on_day: Vec<TestResult> = results
+        .iter()
+        .filter(|r| r.timestamp.with_timezone(&Local).date_naive() == day)
+        .map(|r| (*r).clone())
+        .collect();
+    (1..=on_day.len())
+        .find(|&n| goal.progress(&on_day[..n], day).met)
+        .map(|n| on_day[n - 1].timestamp)
+}
+
+/// Unlocked achievements with the time each was earned
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AchievementData {
+    #[serde(default)]
+    pub unlocked: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl AchievementData {
+    fn get_path() -> PathBuf {
+        let mut path = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        path.push(".ratatap");
+        path.push("achievements.json");
+        path
+    }
+
+    pub fn load() -> Self {
+        let path = Self::get_path();
+        if path.exists()
+            && let Ok(data) = fs::read_to_string(&path)
+            && let Ok(achievements) = serde_json::from_str(&data)
+        {
+            return achievements;
+        }
+        AchievementData::default()
+    }
+
+    pub fn save(&self) {
+        let path = Self::get_path();
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(data) = serde_json::to_string_pretty(self) {
+            let _ = fs::write(path, data);
+        }
+    }
+
+    pub fn unlocked_at(&self, achievement: Achievement) -> Option<DateTime<Utc>> {
+        self.unlocked.get(achievement.id()).copied()
+    }
+
+    /// Unlock every achievement that is now earned, dated by the result that
+    /// earned it. Returns the newly unlocked ones.
+    pub fn evaluate(&mut self, progress: &ProgressData, streaks: &StreakData, goal: &DailyGoal) -> Vec<Achievement> {
+        let now = Utc::now();
+        let mut newly_unlocked = Vec::new();
+        for achievement in Achievement::all() {
+            if self.unlocked_at(achievement).is_none() && achievement.is_earned(progress, streaks) {
+                let earned_at = achievement.earned_at(progress, streaks, goal).unwrap_or(now);
+                self.unlocked.insert(achievement.id().to_string(), earned_at);
+                newly_unlocked.push(achievement);
+            }
+        }
+        newly_unlocked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone};
+
+    fn result(day: u32, wpm: f64) -> TestResult {
+        TestResult {
+            wpm,
+            accuracy: 97.0,
+            timestamp: Local.with_ymd_and_hms(2026, 3, day, 18, 0, 0).unwrap().with_timezone(&Utc),
+            duration_secs: 60,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn backfilled_achievements_are_dated_by_the_result_that_earned_them() {
+        let progress = ProgressData { results: vec![result(1, 35.0), result(2, 45.0), result(3, 62.0), result(4, 50.0)] };
+        let streaks = StreakData {
+            current: 3,
+            longest: 3,
+            last_day: NaiveDate::from_ymd_opt(2026, 3, 4),
+        };
+        let mut achievements = AchievementData::default();
+        let unlocked = achievements.evaluate(&progress, &streaks, &DailyGoal::Tests { count: 1 });
+        assert_eq!(
+            unlocked,
+            [Achievement::FirstTest, Achievement::Wpm40, Achievement::Wpm60, Achievement::Streak3]
+        );
+        let at = |achievement| achievements.unlocked_at(achievement);
+        assert_eq!(at(Achievement::FirstTest), Some(progress.results[0].timestamp));
+        assert_eq!(at(Achievement::Wpm40), Some(progress.results[1].timestamp));
+        assert_eq!(at(Achievement::Wpm60), Some(progress.results[2].timestamp));
+        // The streak running from the 2nd to the 4th reached three days on the 4th
+        assert_eq!(at(Achievement::Streak3), Some(progress.results[3].timestamp));
+    }
+
+    #[test]
+    fn unlocks_are_kept_and_never_redated() {
+        let mut achievements = AchievementData::default();
+        let goal = DailyGoal::default();
+        let progress = ProgressData { results: vec![result(1, 35.0)] };
+        achievements.evaluate(&progress, &StreakData::default(), &goal);
+
+        let progress = ProgressData { results: vec![result(2, 30.0), result(1, 35.0)] };
+        assert!(achievements.evaluate(&progress, &StreakData::default(), &goal).is_empty());
+        assert_eq!(achievements.unlocked_at(Achievement::FirstTest), Some(progress.results[1].timestamp));
+    }
+}
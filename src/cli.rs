@@ -0,0 +1,628 @@
+use chrono::{Local, NaiveDate};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::export::{self, ExportFilter, ExportFormat};
+use crate::goals::{DailyGoal, StreakData};
+use crate::import::{self, ImportSource};
+use crate::modes::code::SeedEntry;
+use crate::modes::words::Difficulty;
+use crate::modes::{Mode, PassageLength};
+use crate::settings::Settings;
+use crate::stats::ProgressData;
+use crate::ui::color_depth::ColorDepth;
+use crate::ui::panels::LayoutPreset;
+use crate::ui::theme::Theme;
+
+pub const USAGE: &str = "\
+Usage: ratatap [OPTIONS] [COMMAND]
+
+Commands:
+  stats            Print a summary of your typing history
+  export [FILE]    Export your history to FILE (or stdout)
+  import FILE      Merge history from ratatap, Monkeytype, keybr or CSV files
+  goal [GOAL]      Show today's goal progress, or set a new daily goal:
+                     goal minutes 20 | goal tests 10 | goal wpm 60 [ACCURACY]
+  help             Show this message
+
+Options:
+  -m, --mode <MODE>      Start in a mode: normal, weak, or a finger drill
+                         (left-pinky, left-ring, ..., right-pinky)
+  -l, --length <LENGTH>  Passage length: short, medium, long
+  -t, --theme <THEME>    Color theme for this session (e.g. nord, dracula)
+  -c, --colors <DEPTH>   Colors the terminal supports: truecolor, 256, 16, mono
+                         (default: detected)
+  --layout <LAYOUT>      Screen layout: full, compact (no logo) or zen (just the
+                         passage and a WPM readout)
+  -L, --language <NAME>  Word list for this session (e.g. english_1k, german)
+  -d, --difficulty <TIER>
+                         Words to draw from: top200, top1k, top10k, rare
+                         (the whole list if it is not ranked that far)
+  -s, --seed <SEED>      Generate the first passage from SEED: a whole number,
+                         or a passage code like normal/medium/english/top200/1234
+                         that also sets the mode, length, word list and options
+  -f, --file <FILE>      Practice the text from FILE instead of generated words
+  --screen-reader        Plain, line-by-line output for screen readers instead of
+                         the full-screen interface
+  -h, --help             Show this message
+  -V, --version          Print version information
+
+Export options:
+  --format <FORMAT>      csv or json (default: from FILE extension, else json)
+  --per-char             Add one error-count column per character (csv only)
+  --from <DATE>          Only tests on or after DATE (YYYY-MM-DD, local time)
+  --to <DATE>            Only tests on or before DATE (YYYY-MM-DD, local time)
+  -m, --mode <MODE>      Only tests taken in MODE
+
+Import options:
+  --source <SOURCE>      ratatap, monkeytype, keybr or csv (default: detected)
+
+Running ratatap without a command launches the typing trainer.";
+
+/// Options for launching the interactive trainer
+#[derive(Debug, Default)]
+pub struct RunOptions {
+    pub mode: Option<Mode>,
+    pub length: Option<PassageLength>,
+    pub theme: Option<Theme>,
+    pub colors: Option<ColorDepth>,
+    pub layout: Option<LayoutPreset>,
+    pub language: Option<String>,
+    pub difficulty: Option<Difficulty>,
+    pub seed: Option<SeedEntry>,
+    pub file: Option<PathBuf>,
+    pub screen_reader: bool,
+}
+
+#[derive(Debug)]
+pub struct ExportOptions {
+    pub output: Option<PathBuf>,
+    pub format: ExportFormat,
+    pub filter: ExportFilter,
+}
+
+#[derive(Debug)]
+pub enum Command {
+    Run(RunOptions),
+    Stats,
+    Export(ExportOptions),
+    Import {
+        input: PathBuf,
+        source: Option<ImportSource>,
+    },
+    /// Show goal progress, or set a new goal when one is given
+    Goal(Option<DailyGoal>),
+    Help,
+    Version,
+}
+
+const COMMANDS: &[&str] = &["stats", "export", "import", "goal", "help"];
+
+/// Flags accepted by each command, used to reject flags that don't apply
+fn allowed_flags(command: Option<&str>) -> &'static [&'static str] {
+    match command {
+        None => &["--mode", "--length", "--theme", "--colors", "--layout", "--language", "--difficulty", "--seed", "--file", "--screen-reader"],
+        Some("export") => &["--mode", "--format", "--per-char", "--from", "--to"],
+        Some("import") => &["--source"],
+        Some(_) => &[],
+    }
+}
+
+/// Where a flag can be used, e.g. "with 'export'"
+fn flag_owner(flag: &str) -> String {
+    let commands: Vec<String> = COMMANDS
+        .iter()
+        .filter(|command| allowed_flags(Some(command)).contains(&flag))
+        .map(|command| format!("'{}'", command))
+        .collect();
+    let mut places = Vec::new();
+    if allowed_flags(None).contains(&flag) {
+        places.push("when starting the trainer".to_string());
+    }
+    if !commands.is_empty() {
+        places.push(format!("with {}", commands.join(" or ")));
+    }
+    places.join(" or ")
+}
+
+/// Parse command-line arguments (without the program name)
+pub fn parse_args<I>(args: I) -> Result<Command, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+    let mut options = RunOptions::default();
+    let mut format: Option<ExportFormat> = None;
+    let mut per_char = false;
+    let mut filter = ExportFilter::default();
+    let mut source: Option<ImportSource> = None;
+    let mut command: Option<String> = None;
+    let mut positional: Vec<String> = Vec::new();
+    let mut seen: Vec<&'static str> = Vec::new();
+
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if arg.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+
+        let mut value = |name: &str| -> Result<String, String> {
+            // A flag in place of the value means the value was left out
+            inline_value
+                .clone()
+                .or_else(|| args.next().filter(|next| !is_flag(next)))
+                .filter(|v| !v.is_empty())
+                .ok_or_else(|| format!("'{}' requires a value", name))
+        };
+
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "-m" | "--mode" => {
+                let name = value("--mode")?;
+                options.mode = Some(Mode::from_name(&name).ok_or_else(|| {
+                    let valid: Vec<&str> = Mode::all().iter().map(|m| m.slug()).collect();
+                    format!("unknown mode '{}' (expected one of: {})", name, valid.join(", "))
+                })?);
+                seen.push("--mode");
+            }
+            "-l" | "--length" => {
+                let name = value("--length")?;
+                options.length = Some(PassageLength::from_name(&name).ok_or_else(|| {
+                    format!("unknown length '{}' (expected one of: short, medium, long)", name)
+                })?);
+                seen.push("--length");
+            }
+            "-t" | "--theme" => {
+                let name = value("--theme")?;
+                options.theme = Some(Theme::from_name(&name).ok_or_else(|| {
+                    let valid: Vec<String> = Theme::all().iter().map(|t| t.name().to_string()).collect();
+                    format!("unknown theme '{}' (expected one of: {})", name, valid.join(", "))
+                })?);
+                seen.push("--theme");
+            }
+            "-c" | "--colors" => {
+                let name = value("--colors")?;
+                options.colors = Some(ColorDepth::from_name(&name).ok_or_else(|| {
+                    format!("unknown color depth '{}' (expected one of: truecolor, 256, 16, mono)", name)
+                })?);
+                seen.push("--colors");
+            }
+            "--layout" => {
+                let name = value("--layout")?;
+                options.layout = Some(LayoutPreset::from_name(&name).ok_or_else(|| {
+                    format!("unknown layout '{}' (expected one of: full, compact, zen)", name)
+                })?);
+                seen.push("--layout");
+            }
+            "-L" | "--language" => {
+                options.language = Some(value("--language")?);
+                seen.push("--language");
+            }
+            "-d" | "--difficulty" => {
+                let name = value("--difficulty")?;
+                options.difficulty = Some(Difficulty::from_name(&name).ok_or_else(|| {
+                    let valid: Vec<&str> = Difficulty::all().iter().map(|d| d.slug()).collect();
+                    format!("unknown difficulty '{}' (expected one of: {})", name, valid.join(", "))
+                })?);
+                seen.push("--difficulty");
+            }
+            "-s" | "--seed" => {
+                options.seed = Some(SeedEntry::parse(&value("--seed")?)?);
+                seen.push("--seed");
+            }
+            "-f" | "--file" => {
+                options.file = Some(PathBuf::from(value("--file")?));
+                seen.push("--file");
+            }
+            "--screen-reader" => {
+                options.screen_reader = true;
+                seen.push("--screen-reader");
+            }
+            "--format" => {
+                let name = value("--format")?;
+                format = Some(match name.to_ascii_lowercase().as_str() {
+                    "csv" => ExportFormat::Csv,
+                    "json" => ExportFormat::Json,
+                    _ => return Err(format!("unknown format '{}' (expected csv or json)", name)),
+                });
+                seen.push("--format");
+            }
+            "--per-char" => {
+                per_char = true;
+                seen.push("--per-char");
+            }
+            "--from" => {
+                filter.from = Some(parse_date("--from", &value("--from")?)?);
+                seen.push("--from");
+            }
+            "--to" => {
+                filter.to = Some(parse_date("--to", &value("--to")?)?);
+                seen.push("--to");
+            }
+            "--source" => {
+                let name = value("--source")?;
+                source = Some(ImportSource::from_name(&name).ok_or_else(|| {
+                    let valid: Vec<&str> = ImportSource::all().iter().map(|s| s.name()).collect();
+                    format!("unknown source '{}' (expected one of: {})", name, valid.join(", "))
+                })?);
+                seen.push("--source");
+            }
+            _ if is_flag(&flag) => {
+                return Err(format!("unknown option '{}'", flag));
+            }
+            _ if command.is_none() => {
+                if !COMMANDS.contains(&arg.as_str()) {
+                    let prefix: String = arg.chars().take(3).collect();
+                    let hint = COMMANDS
+                        .iter()
+                        .find(|c| c.starts_with(&prefix))
+                        .map(|c| format!(" (did you mean '{}'?)", c))
+                        .unwrap_or_default();
+                    return Err(format!("unknown command '{}'{}", arg, hint));
+                }
+                command = Some(arg);
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    let allowed = allowed_flags(command.as_deref());
+    if let Some(flag) = seen.iter().find(|f| !allowed.contains(f)) {
+        return Err(match &command {
+            Some(command) => format!("'{}' cannot be used with '{}' (only {})", flag, command, flag_owner(flag)),
+            None => format!("'{}' can only be used {}", flag, flag_owner(flag)),
+        });
+    }
+
+    // A passage code already says how the passage is generated
+    if let Some(SeedEntry::Code(_)) = options.seed
+        && let Some(flag) = seen.iter().find(|f| ["--mode", "--length", "--language", "--difficulty"].contains(f))
+    {
+        return Err(format!("'{}' cannot be used with a passage code, which sets it already", flag));
+    }
+
+    let Some(command) = command else {
+        return Ok(Command::Run(options));
+    };
+
+    let mut positional = positional.into_iter();
+    let parsed = match command.as_str() {
+        "stats" => Command::Stats,
+        "help" => Command::Help,
+        "export" => {
+            let output = positional.next().map(PathBuf::from);
+            let format = match format.unwrap_or_else(|| {
+                output.as_deref().map(ExportFormat::from_path).unwrap_or(ExportFormat::Json)
+            }) {
+                ExportFormat::Csv if per_char => ExportFormat::CsvWithChars,
+                ExportFormat::Json if per_char => {
+                    return Err("'--per-char' only applies to csv exports".to_string());
+                }
+                format => format,
+            };
+            if let (Some(from), Some(to)) = (filter.from, filter.to)
+                && from > to
+            {
+                return Err(format!("--from {} is after --to {}", from, to));
+            }
+            filter.mode = options.mode;
+            Command::Export(ExportOptions { output, format, filter })
+        }
+        "import" => Command::Import {
+            input: positional
+                .next()
+                .map(PathBuf::from)
+                .ok_or("'import' requires a FILE to read from")?,
+            source,
+        },
+        "goal" => {
+            let args: Vec<String> = positional.by_ref().collect();
+            Command::Goal(if args.is_empty() { None } else { Some(parse_goal(&args)?) })
+        }
+        _ => unreachable!("command validated above"),
+    };
+
+    if let Some(extra) = positional.next() {
+        return Err(format!("unexpected argument '{}' for '{}'", extra, command));
+    }
+
+    Ok(parsed)
+}
+
+/// Whether an argument is an option rather than a value. A lone `-` is a value.
+fn is_flag(arg: &str) -> bool {
+    arg.starts_with('-') && arg.len() > 1
+}
+
+fn parse_goal(args: &[String]) -> Result<DailyGoal, String> {
+    let number = |idx: usize, what: &str| -> Result<f64, String> {
+        let raw = args
+            .get(idx)
+            .ok_or_else(|| format!("'goal {}' requires a {}", args[0], what))?;
+        raw.parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite() && *n > 0.0)
+            .ok_or_else(|| format!("invalid {} '{}' (expected a positive number)", what, raw))
+    };
+    // Minutes and tests are whole numbers, and a goal of zero is no goal
+    let count = |idx: usize, what: &str| -> Result<u32, String> {
+        let n = number(idx, what)?;
+        if n < 1.0 {
+            return Err(format!("invalid {} '{}' (expected at least 1)", what, args[idx]));
+        }
+        if n.round() > u32::MAX as f64 {
+            return Err(format!("invalid {} '{}' (expected at most {})", what, args[idx], u32::MAX));
+        }
+        Ok(n.round() as u32)
+    };
+
+    let (goal, used) = match args[0].to_ascii_lowercase().as_str() {
+        "minutes" | "min" => (DailyGoal::Minutes { minutes: count(1, "number of minutes")? }, 2),
+        "tests" => (DailyGoal::Tests { count: count(1, "number of tests")? }, 2),
+        "wpm" => {
+            let wpm = number(1, "WPM")?;
+            let accuracy = if args.len() > 2 { number(2, "accuracy")? } else { 95.0 };
+            if accuracy > 100.0 {
+                return Err(format!("invalid accuracy '{}' (expected at most 100)", accuracy));
+            }
+            (DailyGoal::Wpm { wpm, accuracy }, 3)
+        }
+        other => {
+            return Err(format!(
+                "unknown goal type '{}' (expected minutes, tests or wpm)",
+                other
+            ));
+        }
+    };
+
+    match args.get(used) {
+        Some(extra) => Err(format!("unexpected argument '{}' for 'goal'", extra)),
+        None => Ok(goal),
+    }
+}
+
+fn parse_date(flag: &str, value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("invalid date '{}' for '{}' (expected YYYY-MM-DD)", value, flag))
+}
+
+/// Print a plain-text summary of the saved history
+pub fn print_stats(progress: &ProgressData) {
+    if progress.results.is_empty() {
+        println!("No tests recorded yet. Run `ratatap` to start practicing.");
+        return;
+    }
+
+    let total_secs = progress.total_practice_secs();
+    println!("Tests completed:   {}", progress.results.len());
+    println!("Average WPM:       {:.1}", progress.average_wpm());
+    println!("Average accuracy:  {:.1}%", progress.average_accuracy());
+    println!("Best WPM:          {:.1}", progress.best_wpm());
+    println!("Time practiced:    {}h {:02}m", total_secs / 3600, (total_secs % 3600) / 60);
+
+    let streaks = StreakData::load();
+    println!(
+        "Streak:            {} days (longest {})",
+        streaks.current_as_of(Local::now().date_naive()),
+        streaks.longest
+    );
+
+    let mut sources: BTreeMap<&str, usize> = BTreeMap::new();
+    for source in progress.results.iter().filter_map(|r| r.source.as_deref()) {
+        *sources.entry(source).or_insert(0) += 1;
+    }
+    if !sources.is_empty() {
+        let counts: Vec<String> = sources.iter().map(|(s, n)| format!("{} {}", s, n)).collect();
+        println!(
+            "Imported tests:    {} ({})",
+            sources.values().sum::<usize>(),
+            counts.join(", ")
+        );
+    }
+
+    let bests = progress.personal_bests();
+    if !bests.is_empty() {
+        println!();
+        println!("Personal bests:");
+        for (key, result) in bests {
+            println!(
+                "  {:<20} {:<7} {:>6.1} WPM  {:>5.1}%  {}  {}",
+                key.mode.name(),
+                key.length.name(),
+                result.wpm,
+                result.accuracy,
+                result.timestamp.with_timezone(&Local).format("%Y-%m-%d"),
+                key.details()
+            );
+        }
+        println!();
+    }
+
+    let weakest = progress.get_weakest_chars(5);
+    if !weakest.is_empty() {
+        let keys: Vec<String> = weakest
+            .iter()
+            .map(|(ch, rate)| format!("'{}' ({:.1})", ch, rate))
+            .collect();
+        println!("Weakest keys:      {}", keys.join(", "));
+    }
+}
+
+/// Export history to a file, or stdout when no file is given
+pub fn export_history(progress: &ProgressData, options: &ExportOptions) -> Result<(), String> {
+    match &options.output {
+        Some(path) => {
+            let count = export::write_export(progress, options.format, &options.filter, path)?;
+            eprintln!("Exported {} tests to {}", count, path.display());
+        }
+        None => print!("{}", export::export(progress, options.format, &options.filter).0),
+    }
+    Ok(())
+}
+
+/// Merge history from another tool or a ratatap export into the saved history
+pub fn import_history(
+    progress: &mut ProgressData,
+    input: &Path,
+    source: Option<ImportSource>,
+) -> Result<(), String> {
+    let report = import::import_file(progress, input, source)?;
+    if report.imported > 0 {
+        progress.save().map_err(|e| format!("could not save history: {}", e))?;
+    }
+
+    println!(
+        "Imported {} of {} tests from {} ({} already present, {} skipped)",
+        report.imported,
+        report.total,
+        report.source.name(),
+        report.duplicates,
+        report.skipped.len()
+    );
+
+    const MAX_LISTED: usize = 20;
+    for skipped in report.skipped.iter().take(MAX_LISTED) {
+        println!("  row {}: {}", skipped.row, skipped.reason);
+    }
+    if report.skipped.len() > MAX_LISTED {
+        println!("  ... and {} more", report.skipped.len() - MAX_LISTED);
+    }
+
+    Ok(())
+}
+
+/// Show progress toward the daily goal, or save a new goal
+pub fn goal(progress: &ProgressData, new_goal: Option<DailyGoal>) {
+    let mut settings = Settings::load();
+    if let Some(goal) = new_goal {
+        settings.daily_goal = goal;
+        settings.save();
+        println!("Daily goal set to {}", goal.name());
+        return;
+    }
+
+    let today = Local::now().date_naive();
+    let goal_progress = settings.daily_goal.progress(&progress.results, today);
+    let streaks = StreakData::load();
+    println!("Daily goal:        {}", settings.daily_goal.name());
+    println!(
+        "Today:             {:.0}% {}",
+        goal_progress.ratio() * 100.0,
+        if goal_progress.met { "(met)" } else { "(not met yet)" }
+    );
+    println!(
+        "Streak:            {} days (longest {})",
+        streaks.current_as_of(today),
+        streaks.longest
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Command, String> {
+        parse_args(args.split_whitespace().map(String::from))
+    }
+
+    fn run_options(args: &str) -> RunOptions {
+        match parse(args) {
+            Ok(Command::Run(options)) => options,
+            other => panic!("expected run options from '{}', got {:?}", args, other),
+        }
+    }
+
+    #[test]
+    fn parses_launch_flags() {
+        let options = run_options("--mode weak -l long --seed=42 --file notes.txt");
+        assert_eq!(options.mode, Some(Mode::WeakLetter));
+        assert_eq!(options.length, Some(PassageLength::Long));
+        assert_eq!(options.seed, Some(SeedEntry::Seed(42)));
+        assert_eq!(options.file, Some(PathBuf::from("notes.txt")));
+        assert!(!options.screen_reader);
+        assert!(matches!(parse("--help"), Ok(Command::Help)));
+        assert!(matches!(parse(""), Ok(Command::Run(_))));
+    }
+
+    #[test]
+    fn a_flag_is_never_taken_as_a_value() {
+        assert_eq!(parse("--mode --length short").unwrap_err(), "'--mode' requires a value");
+        assert_eq!(parse("--seed").unwrap_err(), "'--seed' requires a value");
+        // Only values given with '=' may start with a dash
+        assert!(parse("--seed=-1").unwrap_err().starts_with("invalid seed '-1'"));
+    }
+
+    #[test]
+    fn a_passage_code_sets_the_passage_options() {
+        let options = run_options("--seed left-index/short/german/top1k,madeup/9");
+        let Some(SeedEntry::Code(code)) = options.seed else {
+            panic!("expected a passage code");
+        };
+        assert_eq!(code.mode, Mode::from_name("left-index").unwrap());
+        assert_eq!((code.length, code.language.as_str(), code.seed), (PassageLength::Short, "german", 9));
+        assert!(code.words.pseudo_words);
+        assert_eq!(
+            parse("--seed normal/short/german/top1k/9 --length long").unwrap_err(),
+            "'--length' cannot be used with a passage code, which sets it already"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_and_misplaced_flags() {
+        assert_eq!(parse("--bogus").unwrap_err(), "unknown option '--bogus'");
+        assert_eq!(parse("--length huge").unwrap_err(), "unknown length 'huge' (expected one of: short, medium, long)");
+        assert_eq!(parse("stast").unwrap_err(), "unknown command 'stast' (did you mean 'stats'?)");
+        assert_eq!(
+            parse("stats --seed 1").unwrap_err(),
+            "'--seed' cannot be used with 'stats' (only when starting the trainer)"
+        );
+        assert_eq!(parse("--per-char").unwrap_err(), "'--per-char' can only be used with 'export'");
+        assert_eq!(parse("--source keybr").unwrap_err(), "'--source' can only be used with 'import'");
+        assert_eq!(
+            parse("export --source keybr").unwrap_err(),
+            "'--source' cannot be used with 'export' (only with 'import')"
+        );
+        assert_eq!(
+            parse("import a.csv --mode weak").unwrap_err(),
+            "'--mode' cannot be used with 'import' (only when starting the trainer or with 'export')"
+        );
+        assert_eq!(parse("stats extra").unwrap_err(), "unexpected argument 'extra' for 'stats'");
+    }
+
+    #[test]
+    fn parses_export_options() {
+        let Ok(Command::Export(options)) = parse("export out.csv --per-char --mode weak --from 2026-01-01") else {
+            panic!("expected an export");
+        };
+        assert_eq!(options.output, Some(PathBuf::from("out.csv")));
+        assert_eq!(options.format, ExportFormat::CsvWithChars);
+        assert_eq!(options.filter.mode, Some(Mode::WeakLetter));
+        assert_eq!(options.filter.from, NaiveDate::from_ymd_opt(2026, 1, 1));
+
+        let Ok(Command::Export(options)) = parse("export") else {
+            panic!("expected an export");
+        };
+        assert_eq!((options.output, options.format), (None, ExportFormat::Json));
+        assert_eq!(parse("export --per-char").unwrap_err(), "'--per-char' only applies to csv exports");
+        assert_eq!(parse("export --from 2026-02-01 --to 2026-01-01").unwrap_err(), "--from 2026-02-01 is after --to 2026-01-01");
+    }
+
+    #[test]
+    fn goal_counts_are_at_least_one() {
+        assert!(matches!(parse("goal tests 3"), Ok(Command::Goal(Some(DailyGoal::Tests { count: 3 })))));
+        assert!(matches!(parse("goal min 1.4"), Ok(Command::Goal(Some(DailyGoal::Minutes { minutes: 1 })))));
+        assert_eq!(parse("goal tests 0.3").unwrap_err(), "invalid number of tests '0.3' (expected at least 1)");
+        assert_eq!(parse("goal minutes 0").unwrap_err(), "invalid number of minutes '0' (expected a positive number)");
+        assert!(matches!(parse("goal wpm 0.5"), Ok(Command::Goal(Some(DailyGoal::Wpm { .. })))));
+        assert_eq!(parse("goal tests 1e12").unwrap_err(), "invalid number of tests '1e12' (expected at most 4294967295)");
+    }
+
+    #[test]
+    fn goals_must_be_finite() {
+        assert_eq!(parse("goal tests inf").unwrap_err(), "invalid number of tests 'inf' (expected a positive number)");
+        assert_eq!(parse("goal wpm inf").unwrap_err(), "invalid WPM 'inf' (expected a positive number)");
+        assert_eq!(parse("goal wpm NaN").unwrap_err(), "invalid WPM 'NaN' (expected a positive number)");
+        assert_eq!(parse("goal wpm 60 0").unwrap_err(), "invalid accuracy '0' (expected a positive number)");
+    }
+}
@@ -0,0 +1,428 @@
+//! Export of test history for use in spreadsheets and other tools.
+//!
+//! Two formats are supported:
+//!
+//! * **CSV** - one row per test with the columns `timestamp`, `date`, `mode`,
+//!   `length`, `input_mode`, `seed`, `retry_of`, `wpm`, `accuracy`,
+//!   `word_accuracy`, `duration_secs`, `errors`, `language`, `word_options`,
+//!   `custom_passage`, `source` and `import_id`. `word_options` is in passage
+//!   code form, e.g. `top1k,min3,madeup`.
+//!   When per-char columns are requested, one extra `err_<char>` column is
+//!   added for every character that was mistyped in any exported test.
+//! * **JSON** - a versioned document with a stable schema:
+//!
+//! ```json
+//! {
+//!   "format": "ratatap-history",
+//!   "version": 1,
+//!   "exported_at": "2026-03-01T18:22:05Z",
+//!   "tests": [
+//!     {
+//!       "timestamp": "2026-02-28T20:11:43Z",
+//!       "mode": "weak",
+//!       "length": "medium",
+//!       "input_mode": "normal",
+//!       "seed": 482913,
+//!       "retry_of": null,
+//!       "wpm": 61.4,
+//!       "accuracy": 96.2,
+//!       "word_accuracy": 88.0,
+//!       "duration_secs": 41,
+//!       "errors": 3,
+//!       "char_errors": [{ "char": "e", "count": 2 }, { "char": "r", "count": 1 }],
+//!       "language": "english",
+//!       "word_options": {
+//!         "difficulty": "top1k",
+//!         "avoid_repeats": true,
+//!         "min_length": 3,
+//!         "max_length": null,
+//!         "pseudo_words": false
+//!       },
+//!       "custom_passage": null,
+//!       "source": null,
+//!       "import_id": null
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! `mode` and `length` are `null` for custom passages and for results recorded
+//! before ratatap stored them. `input_mode` is one of `normal`,
+//! `stop_on_letter`, `stop_on_word` or `must_correct`. `word_accuracy` is
+//! `null` for results recorded before ratatap tracked it. `seed` is the seed the
+//! passage was generated from, or `null` for custom passages and older results.
+//! `retry_of` is the `timestamp` of the first attempt at the same passage, or
+//! `null` when the test was not a retry. `language` and `word_options` are
+//! what the passage was generated with, `null` for custom passages and older
+//! results; `custom_passage` is the file a custom passage was loaded from.
+//! `source` is the tool an imported test came from (e.g. `monkeytype`) and
+//! `import_id` the id that tool gave it, both `null` for tests taken in
+//! ratatap. Importing an export keeps them, so re-imported tests stay tagged
+//! and are still recognised as duplicates. `source` and `import_id` were
+//! added in version 1 and may be missing from older exports.
+//! Fields will only ever be added within a version;
+//! any incompatible change bumps `version`.
+
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::input_mode::InputMode;
+use crate::modes::words::WordOptions;
+use crate::modes::Mode;
+use crate::stats::{ProgressData, TestResult};
+
+pub const JSON_FORMAT_NAME: &str = "ratatap-history";
+pub const JSON_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    CsvWithChars,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn next(&self) -> Self {
+        match self {
+            ExportFormat::Csv => ExportFormat::CsvWithChars,
+            ExportFormat::CsvWithChars => ExportFormat::Json,
+            ExportFormat::Json => ExportFormat::Csv,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::CsvWithChars => "CSV + per-char errors",
+            ExportFormat::Json => "JSON",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv | ExportFormat::CsvWithChars => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+
+    /// Guess the format from a file extension, defaulting to JSON
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => ExportFormat::Csv,
+            _ => ExportFormat::Json,
+        }
+    }
+}
+
+/// Which tests to include in an export. Dates are inclusive and compared in local time.
+#[derive(Debug, Clone, Default)]
+pub struct ExportFilter {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub mode: Option<Mode>,
+}
+
+impl ExportFilter {
+    pub fn matches(&self, result: &TestResult) -> bool {
+        let date = result.timestamp.with_timezone(&Local).date_naive();
+        if self.from.is_some_and(|from| date < from) || self.to.is_some_and(|to| date > to) {
+            return false;
+        }
+        match self.mode {
+            Some(mode) => result.mode == Some(mode),
+            None => true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ExportDocument {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub tests: Vec<ExportedTest>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ExportedTest {
+    pub timestamp: DateTime<Utc>,
+    pub mode: Option<String>,
+    pub length: Option<String>,
+    #[serde(default)]
+    pub input_mode: InputMode,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub retry_of: Option<DateTime<Utc>>,
+    pub wpm: f64,
+    pub accuracy: f64,
+    #[serde(default)]
+    pub word_accuracy: Option<f64>,
+    pub duration_secs: u64,
+    pub errors: usize,
+    pub char_errors: Vec<CharErrorCount>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub word_options: Option<WordOptions>,
+    #[serde(default)]
+    pub custom_passage: Option<String>,
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub import_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CharErrorCount {
+    pub char: char,
+    pub count: usize,
+}
+
+/// Render the matching results in the given format.
+/// Returns the rendered data and the number of tests it contains.
+pub fn export(progress: &ProgressData, format: ExportFormat, filter: &ExportFilter) -> (String, usize) {
+    let results: Vec<&TestResult> = progress.results.iter().filter(|r| filter.matches(r)).collect();
+    let data = match format {
+        ExportFormat::Csv => to_csv(&results, false),
+        ExportFormat::CsvWithChars => to_csv(&results, true),
+        ExportFormat::Json => to_json(&results),
+    };
+    (data, results.len())
+}
+
+/// Default location for exports made from inside the app
+pub fn default_export_path(format: ExportFormat) -> PathBuf {
+    let mut path = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push(".ratatap");
+    path.push("exports");
+    path.push(format!(
+        "ratatap-{}.{}",
+        Local::now().format("%Y%m%d-%H%M%S"),
+        format.extension()
+    ));
+    path
+}
+
+/// Export to a file, creating parent directories as needed.
+/// Returns the number of tests written.
+pub fn write_export(
+    progress: &ProgressData,
+    format: ExportFormat,
+    filter: &ExportFilter,
+    path: &Path,
+) -> Result<usize, String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("could not create '{}': {}", parent.display(), e))?;
+    }
+    let (data, count) = export(progress, format, filter);
+    fs::write(path, data).map_err(|e| format!("could not write '{}': {}", path.display(), e))?;
+    Ok(count)
+}
+
+fn to_csv(results: &[&TestResult], per_char: bool) -> String {
+    let chars: BTreeSet<char> = if per_char {
+        results.iter().flat_map(|r| r.char_errors.keys().copied()).collect()
+    } else {
+        BTreeSet::new()
+    };
+
+    let mut header: Vec<String> = [
+        "timestamp", "date", "mode", "length", "input_mode", "seed", "retry_of", "wpm",
+        "accuracy", "word_accuracy", "duration_secs", "errors", "language", "word_options",
+        "custom_passage", "source", "import_id",
+    ]
+    .iter()
+    .map(|h| h.to_string())
+    .collect();
+    header.extend(chars.iter().map(|ch| format!("err_{}", ch)));
+
+    let mut out = csv_row(&header);
+    for result in results {
+        let mut row = vec![
+            result.timestamp.to_rfc3339(),
+            result.timestamp.with_timezone(&Local).format("%Y-%m-%d").to_string(),
+            result.mode.map(|m| m.slug().to_string()).unwrap_or_default(),
+            result.length.map(|l| l.name().to_lowercase()).unwrap_or_default(),
+            result.input_mode.slug().to_string(),
+            result.seed.map(|s| s.to_string()).unwrap_or_default(),
+            result.retry_of.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            format!("{:.2}", result.wpm),
+            format!("{:.2}", result.accuracy),
+            result.word_accuracy.map(|a| format!("{:.2}", a)).unwrap_or_default(),
+            result.duration_secs.to_string(),
+            result.char_errors.values().sum::<usize>().to_string(),
+            result.language.clone().unwrap_or_default(),
+            result.word_options.map(|o| o.tags()).unwrap_or_default(),
+            result.custom_passage.clone().unwrap_or_default(),
+            result.source.clone().unwrap_or_default(),
+            result.import_id.clone().unwrap_or_default(),
+        ];
+        row.extend(
+            chars
+                .iter()
+                .map(|ch| result.char_errors.get(ch).copied().unwrap_or(0).to_string()),
+        );
+        out.push_str(&csv_row(&row));
+    }
+    out
+}
+
+fn to_json(results: &[&TestResult]) -> String {
+    let tests = results
+        .iter()
+        .map(|r| {
+            let mut char_errors: Vec<CharErrorCount> = r
+                .char_errors
+                .iter()
+                .map(|(&char, &count)| CharErrorCount { char, count })
+                .collect();
+            char_errors.sort_by_key(|c| c.char);
+
+            ExportedTest {
+                timestamp: r.timestamp,
+                mode: r.mode.map(|m| m.slug().to_string()),
+                length: r.length.map(|l| l.name().to_lowercase()),
+                input_mode: r.input_mode,
+                seed: r.seed,
+                retry_of: r.retry_of,
+                wpm: r.wpm,
+                accuracy: r.accuracy,
+                word_accuracy: r.word_accuracy,
+                duration_secs: r.duration_secs,
+                errors: r.char_errors.values().sum(),
+                char_errors,
+                language: r.language.clone(),
+                word_options: r.word_options,
+                custom_passage: r.custom_passage.clone(),
+                source: r.source.clone(),
+                import_id: r.import_id.clone(),
+            }
+        })
+        .collect();
+
+    let document = ExportDocument {
+        format: JSON_FORMAT_NAME.to_string(),
+        version: JSON_FORMAT_VERSION,
+        exported_at: Utc::now(),
+        tests,
+    };
+    let mut data = serde_json::to_string_pretty(&document).unwrap_or_default();
+    data.push('\n');
+    data
+}
+
+fn csv_row(fields: &[String]) -> String {
+    let escaped: Vec<String> = fields.iter().map(|f| csv_escape(f)).collect();
+    format!("{}\n", escaped.join(","))
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) || field.starts_with(' ') || field.ends_with(' ') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modes::PassageLength;
+    use chrono::TimeZone;
+
+    fn result(day: u32, mode: Mode, char_errors: &[(char, usize)]) -> TestResult {
+        TestResult {
+            wpm: 61.4,
+            accuracy: 96.25,
+            timestamp: Utc.with_ymd_and_hms(2026, 2, day, 12, 0, 0).unwrap(),
+            duration_secs: 41,
+            char_errors: char_errors.iter().copied().collect(),
+            mode: Some(mode),
+            length: Some(PassageLength::Medium),
+            seed: Some(7),
+            language: Some("english".to_string()),
+            word_options: Some(WordOptions {
+                difficulty: crate::modes::words::Difficulty::Top1k,
+                min_length: Some(3),
+                ..WordOptions::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn history() -> ProgressData {
+        ProgressData {
+            results: vec![
+                result(1, Mode::Normal, &[('e', 2), ('r', 1)]),
+                result(10, Mode::WeakLetter, &[(',', 1)]),
+                result(20, Mode::Normal, &[]),
+            ],
+        }
+    }
+
+    #[test]
+    fn csv_has_a_fixed_header_and_one_row_per_test() {
+        let (csv, count) = export(&history(), ExportFormat::Csv, &ExportFilter::default());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(count, 3);
+        assert_eq!(
+            lines[0],
+            "timestamp,date,mode,length,input_mode,seed,retry_of,wpm,accuracy,word_accuracy,duration_secs,errors,\
+             language,word_options,custom_passage,source,import_id"
+        );
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].ends_with(",normal,medium,normal,7,,61.40,96.25,,41,3,english,\"top1k,min3\",,,"), "{}", lines[1]);
+    }
+
+    #[test]
+    fn per_char_columns_cover_every_mistyped_char() {
+        let (csv, _) = export(&history(), ExportFormat::CsvWithChars, &ExportFilter::default());
+        let lines: Vec<&str> = csv.lines().collect();
+        // The comma column is quoted so the header keeps its shape
+        assert!(lines[0].ends_with(",import_id,\"err_,\",err_e,err_r"), "{}", lines[0]);
+        assert!(lines[1].ends_with(",0,2,1"));
+        assert!(lines[2].ends_with(",1,0,0"));
+        assert!(lines[3].ends_with(",0,0,0"));
+    }
+
+    #[test]
+    fn filters_by_date_and_mode() {
+        let filter = ExportFilter {
+            from: NaiveDate::from_ymd_opt(2026, 2, 5),
+            to: NaiveDate::from_ymd_opt(2026, 2, 25),
+            mode: Some(Mode::Normal),
+        };
+        let (json, count) = export(&history(), ExportFormat::Json, &filter);
+        assert_eq!(count, 1);
+        let document: ExportDocument = serde_json::from_str(&json).unwrap();
+        assert_eq!(document.tests[0].timestamp, history().results[2].timestamp);
+    }
+
+    #[test]
+    fn json_document_keeps_its_schema() {
+        let (json, _) = export(&history(), ExportFormat::Json, &ExportFilter::default());
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["format"], JSON_FORMAT_NAME);
+        assert_eq!(value["version"], JSON_FORMAT_VERSION);
+
+        let test = &value["tests"][0];
+        let mut keys: Vec<&str> = test.as_object().unwrap().keys().map(String::as_str).collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            [
+                "accuracy", "char_errors", "custom_passage", "duration_secs", "errors", "import_id",
+                "input_mode", "language", "length", "mode", "retry_of", "seed", "source", "timestamp",
+                "word_accuracy", "word_options", "wpm",
+            ]
+        );
+        assert_eq!(test["mode"], "normal");
+        assert_eq!(test["word_options"]["difficulty"], "top1k");
+        assert_eq!(test["word_options"]["min_length"], 3);
+        assert_eq!(test["char_errors"], serde_json::json!([{ "char": "e", "count": 2 }, { "char": "r", "count": 1 }]));
+    }
+}
@@ -26,6 +26,24 @@ impl Finger {
         }
     }
 
+    /// Short, command-line friendly identifier (e.g. `left-pinky`)
+    pub fn slug(&self) -> &'static str {
+        match self {
+            Finger::LeftPinky => "left-pinky",
+            Finger::LeftRing => "left-ring",
+            Finger::LeftMiddle => "left-middle",
+            Finger::LeftIndex => "left-index",
+            Finger::RightIndex => "right-index",
+            Finger::RightMiddle => "right-middle",
+            Finger::RightRing => "right-ring",
+            Finger::RightPinky => "right-pinky",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Finger> {
+        Finger::all().into_iter().find(|f| f.slug() == name)
+    }
+
     pub fn all() -> Vec<Finger> {
         vec![
             Finger::LeftPinky,
@@ -58,3 +76,111 @@ pub fn get_keys_for_finger(finger: Finger) -> Vec<char> {
         Finger::RightPinky => vec!['0', '-', '=', 'p', '[', ']', ';', '\'', '/', '\\'],
     }
 }
+
+/// Finger that types `key` on a QWERTY keyboard, accepting shifted keys.
+/// `None` for the space bar and keys outside the main block.
+pub fn finger_for_key(key: char) -> Option<Finger> {
+    let key = base_key(key);
+    Finger::all()
+        .into_iter()
+        .find(|&finger| get_keys_for_finger(finger).contains(&key))
+}
+
+/// Keyboard row that `key` sits on, accepting shifted keys
+pub fn row_for_key(key: char) -> Option<Row> {
+    let key = base_key(key);
+    Row::all().into_iter().find(|row| row.keys().contains(key))
+}
+
+/// Characters typed with Shift, in the same order as their base keys in `BASE`
+const SHIFTED: &str = "~!@#$%^&*()_+{}|:\"<>?";
+const BASE: &str = "`1234567890-=[]\\;',./";
+
+/// The key pressed (without Shift) to type `key`
+pub fn base_key(key: char) -> char {
+    match SHIFTED.chars().position(|c| c == key) {
+        Some(i) => BASE.chars().nth(i).unwrap_or(key),
+        None => key.to_lowercase().next().unwrap_or(key),
+    }
+}
+
+/// What `key` types with Shift held
+pub fn shifted_key(key: char) -> char {
+    match BASE.chars().position(|c| c == key) {
+        Some(i) => SHIFTED.chars().nth(i).unwrap_or(key),
+        None => key.to_uppercase().next().unwrap_or(key),
+    }
+}
+
+/// Whether typing `key` on QWERTY takes Shift (capitals and shifted symbols)
+pub fn needs_shift(key: char) -> bool {
+    key.is_ascii_uppercase() || SHIFTED.contains(key)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+impl Hand {
+    pub fn all() -> [Hand; 2] {
+        [Hand::Left, Hand::Right]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Hand::Left => "Left hand",
+            Hand::Right => "Right hand",
+        }
+    }
+
+    /// Shift is pressed with the hand not striking the key
+    pub fn other(&self) -> Hand {
+        match self {
+            Hand::Left => Hand::Right,
+            Hand::Right => Hand::Left,
+        }
+    }
+}
+
+impl Finger {
+    pub fn hand(&self) -> Hand {
+        match self {
+            Finger::LeftPinky | Finger::LeftRing | Finger::LeftMiddle | Finger::LeftIndex => Hand::Left,
+            _ => Hand::Right,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Row {
+    Number,
+    Top,
+    Home,
+    Bottom,
+}
+
+impl Row {
+    pub fn all() -> [Row; 4] {
+        [Row::Number, Row::Top, Row::Home, Row::Bottom]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Row::Number => "Number row",
+            Row::Top => "Top row",
+            Row::Home => "Home row",
+            Row::Bottom => "Bottom row",
+        }
+    }
+
+    fn keys(&self) -> &'static str {
+        match self {
+            Row::Number => "`1234567890-=",
+            Row::Top => "qwertyuiop[]\\",
+            Row::Home => "asdfghjkl;'",
+            Row::Bottom => "zxcvbnm,./",
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! Per-finger, per-hand and per-row performance, rolled up from the
+//! per-character errors and keystroke timings stored with each result.
+//!
+//! Error counts cover every result. Accuracy and latency need the number of
+//! presses, which is only recorded for tests taken in ratatap since
+//! keystroke tracking was added, so they are computed from those alone.
+
+use std::collections::HashMap;
+
+use crate::finger_map::{base_key, finger_for_key, row_for_key, Finger, Hand, Row};
+use crate::stats::{KeyStats, TestResult};
+
+/// Presses a finger needs before it can be called the weakest
+const MIN_PRESSES: usize = 20;
+
+/// Totals for a group of keys
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct KeyGroup {
+    /// Mistakes across every result
+    pub errors: usize,
+    pub presses: usize,
+    /// Mistakes in results that also recorded presses
+    measured_errors: usize,
+    timed: usize,
+    total_ms: u64,
+}
+
+impl KeyGroup {
+    fn add_errors(&mut self, errors: usize, measured: bool) {
+        self.errors += errors;
+        if measured {
+            self.measured_errors += errors;
+        }
+    }
+
+    fn add_presses(&mut self, stats: &KeyStats) {
+        self.presses += stats.presses;
+        self.timed += stats.timed;
+        self.total_ms += stats.total_ms;
+    }
+
+    fn merge(&mut self, other: &KeyGroup) {
+        self.errors += other.errors;
+        self.presses += other.presses;
+        self.measured_errors += other.measured_errors;
+        self.timed += other.timed;
+        self.total_ms += other.total_ms;
+    }
+
+    /// Share of presses that were correct, once any presses are recorded
+    pub fn accuracy(&self) -> Option<f64> {
+        (self.presses > 0).then(|| {
+            let correct = self.presses.saturating_sub(self.measured_errors);
+            correct as f64 / self.presses as f64 * 100.0
+        })
+    }
+
+    /// Mean time from the previous keystroke, in milliseconds
+    pub fn average_latency_ms(&self) -> Option<f64> {
+        (self.timed > 0).then(|| self.total_ms as f64 / self.timed as f64)
+    }
+}
+
+/// Totals per key, with shifted characters counted on their base key
+pub fn key_totals<'a>(results: impl IntoIterator<Item = &'a TestResult>) -> HashMap<char, KeyGroup> {
+    let mut totals: HashMap<char, KeyGroup> = HashMap::new();
+    for result in results {
+        let measured = !result.key_stats.is_empty();
+        for (&key, &errors) in &result.char_errors {
+            totals.entry(base_key(key)).or_default().add_errors(errors, measured);
+        }
+        for (&key, stats) in &result.key_stats {
+            totals.entry(base_key(key)).or_default().add_presses(stats);
+        }
+    }
+    totals
+}
+
+pub struct FingerReport {
+    pub fingers: Vec<(Finger, KeyGroup)>,
+    pub hands: Vec<(Hand, KeyGroup)>,
+    pub rows: Vec<(Row, KeyGroup)>,
+}
+
+impl FingerReport {
+    pub fn new<'a>(results: impl IntoIterator<Item = &'a TestResult>) -> Self {
+        let mut report = FingerReport {
+            fingers: Finger::all().into_iter().map(|f| (f, KeyGroup::default())).collect(),
+            hands: Hand::all().into_iter().map(|h| (h, KeyGroup::default())).collect(),
+            rows: Row::all().into_iter().map(|r| (r, KeyGroup::default())).collect(),
+        };
+
+        for (key, totals) in key_totals(results) {
+            for group in report.groups_for(key) {
+                group.merge(&totals);
+            }
+        }
+
+        report
+    }
+
+    /// The finger, hand and row totals a key counts toward
+    fn groups_for(&mut self, key: char) -> Vec<&mut KeyGroup> {
+        let finger = finger_for_key(key);
+        let hand = finger.map(|f| f.hand());
+        let row = row_for_key(key);
+
+        let fingers = self.fingers.iter_mut().filter(|(f, _)| Some(*f) == finger).map(|(_, g)| g);
+        let hands = self.hands.iter_mut().filter(|(h, _)| Some(*h) == hand).map(|(_, g)| g);
+        let rows = self.rows.iter_mut().filter(|(r, _)| Some(*r) == row).map(|(_, g)| g);
+        fingers.chain(hands).chain(rows).collect()
+    }
+
+    /// The finger most in need of a drill: the least accurate one with
+    /// enough presses recorded (the slower one on a tie), or failing that
+    /// the one with the most errors
+    pub fn weakest_finger(&self) -> Option<Finger> {
+        let measured = self
+            .fingers
+            .iter()
+            .filter(|(_, group)| group.presses >= MIN_PRESSES)
+            .filter_map(|(finger, group)| Some((*finger, group.accuracy()?, group.average_latency_ms())))
+            .min_by(|a, b| a.1.total_cmp(&b.1).then(b.2.unwrap_or(0.0).total_cmp(&a.2.unwrap_or(0.0))));
+        if let Some((finger, _, _)) = measured {
+            return Some(finger);
+        }
+
+        self.fingers
+            .iter()
+            .filter(|(_, group)| group.errors > 0)
+            .max_by_key(|(_, group)| group.errors)
+            .map(|(finger, _)| *finger)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn result(errors: &[(char, usize)], presses: &[(char, usize, u64)]) -> TestResult {
+        TestResult {
+            char_errors: errors.iter().copied().collect(),
+            key_stats: presses
+                .iter()
+                .map(|&(key, presses, total_ms)| (key, KeyStats { presses, timed: presses, total_ms }))
+                .collect::<HashMap<_, _>>(),
+            ..Default::default()
+        }
+    }
+
+    fn group<T: PartialEq + Copy>(groups: &[(T, KeyGroup)], which: T) -> KeyGroup {
+        groups.iter().find(|(t, _)| *t == which).unwrap().1
+    }
+
+    #[test]
+    fn rolls_keys_up_to_fingers_hands_and_rows() {
+        let results = [result(&[('f', 2), ('G', 1)], &[('f', 30, 3000), ('g', 10, 2000), ('j', 40, 4000)])];
+        let report = FingerReport::new(&results);
+
+        let index = group(&report.fingers, Finger::LeftIndex);
+        assert_eq!((index.errors, index.presses), (3, 40));
+        assert_eq!(index.accuracy(), Some(92.5));
+        assert_eq!(index.average_latency_ms(), Some(125.0));
+
+        let right = group(&report.hands, Hand::Right);
+        assert_eq!((right.errors, right.presses), (0, 40));
+        assert_eq!(group(&report.rows, Row::Home).presses, 80);
+        assert_eq!(group(&report.rows, Row::Top).presses, 0);
+    }
+
+    #[test]
+    fn older_results_count_errors_but_not_accuracy() {
+        let results = [result(&[('a', 5)], &[]), result(&[('a', 1)], &[('a', 10, 1000)])];
+        let pinky = group(&FingerReport::new(&results).fingers, Finger::LeftPinky);
+        assert_eq!(pinky.errors, 6);
+        assert_eq!(pinky.accuracy(), Some(90.0));
+    }
+
+    #[test]
+    fn recommends_the_least_accurate_finger() {
+        let results = [result(
+            &[('k', 2), ('a', 1), ('p', 9)],
+            &[('k', 20, 2000), ('a', 40, 4000), ('p', 5, 500)],
+        )];
+        // The right pinky has the most errors but too few presses to judge
+        assert_eq!(FingerReport::new(&results).weakest_finger(), Some(Finger::RightMiddle));
+
+        let unmeasured = [result(&[('s', 3), (';', 4)], &[])];
+        assert_eq!(FingerReport::new(&unmeasured).weakest_finger(), Some(Finger::RightPinky));
+        assert_eq!(FingerReport::new(&[]).weakest_finger(), None);
+    }
+}
@@ -0,0 +1,218 @@
+use chrono::{Days, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::stats::TestResult;
+
+/// What counts as a successful day of practice
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DailyGoal {
+    /// Total minutes spent in tests
+    Minutes { minutes: u32 },
+    /// Number of completed tests
+    Tests { count: u32 },
+    /// At least one test at or above a WPM while meeting an accuracy floor
+    Wpm { wpm: f64, accuracy: f64 },
+}
+
+impl Default for DailyGoal {
+    fn default() -> Self {
+        DailyGoal::Minutes { minutes: 15 }
+    }
+}
+
+/// Progress toward a goal for a single day
+#[derive(Debug, Clone, Copy)]
+pub struct GoalProgress {
+    pub current: f64,
+    pub target: f64,
+    pub met: bool,
+}
+
+impl GoalProgress {
+    pub fn ratio(&self) -> f64 {
+        if self.target > 0.0 {
+            (self.current / self.target).clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+}
+
+const PRESETS: &[DailyGoal] = &[
+    DailyGoal::Minutes { minutes: 10 },
+    DailyGoal::Minutes { minutes: 15 },
+    DailyGoal::Minutes { minutes: 30 },
+    DailyGoal::Tests { count: 5 },
+    DailyGoal::Tests { count: 10 },
+    DailyGoal::Tests { count: 20 },
+    DailyGoal::Wpm { wpm: 40.0, accuracy: 95.0 },
+    DailyGoal::Wpm { wpm: 60.0, accuracy: 95.0 },
+    DailyGoal::Wpm { wpm: 80.0, accuracy: 95.0 },
+];
+
+impl DailyGoal {
+    pub fn name(&self) -> String {
+        match self {
+            DailyGoal::Minutes { minutes } => format!("{} min", minutes),
+            DailyGoal::Tests { count } => format!("{} test{}", count, if *count == 1 { "" } else { "s" }),
+            DailyGoal::Wpm { wpm, accuracy } => format!("{:.0} WPM @ {:.0}%", wpm, accuracy),
+        }
+    }
+
+    /// Cycle through the built-in presets (custom goals jump to the first preset)
+    pub fn next_preset(&self) -> DailyGoal {
+        let idx = PRESETS.iter().position(|g| g == self);
+        match idx {
+            Some(i) => PRESETS[(i + 1) % PRESETS.len()],
+            None => PRESETS[0],
+        }
+    }
+
+    /// Progress toward this goal on a given local day
+    pub fn progress(&self, results: &[TestResult], day: NaiveDate) -> GoalProgress {
+        let todays: Vec<&TestResult> = results
+            .iter()
+            .filter(|r| r.timestamp.with_timezone(&Local).date_naive() == day)
+            .collect();
+
+        let (current, target) = match *self {
+            DailyGoal::Minutes { minutes } => {
+                let secs: u64 = todays.iter().map(|r| r.duration_secs).sum();
+                (secs as f64 / 60.0, minutes as f64)
+            }
+            DailyGoal::Tests { count } => (todays.len() as f64, count as f64),
+            DailyGoal::Wpm { wpm, accuracy } => {
+                let best = todays
+                    .iter()
+                    .filter(|r| r.accuracy >= accuracy)
+                    .map(|r| r.wpm)
+                    .fold(0.0, f64::max);
+                (best, wpm)
+            }
+        };
+
+        GoalProgress {
+            current,
+            target,
+            met: current >= target,
+        }
+    }
+}
+
+/// Consecutive days on which the daily goal was met
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StreakData {
+    pub current: u32,
+    pub longest: u32,
+    pub last_day: Option<NaiveDate>,
+}
+
+impl StreakData {
+    fn get_path() -> PathBuf {
+        let mut path = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        path.push(".ratatap");
+        path.push("streaks.json");
+        path
+    }
+
+    pub fn load() -> Self {
+        let path = Self::get_path();
+        if path.exists()
+            && let Ok(data) = fs::read_to_string(&path)
+            && let Ok(streaks) = serde_json::from_str(&data)
+        {
+            return streaks;
+        }
+        StreakData::default()
+    }
+
+    pub fn save(&self) {
+        let path = Self::get_path();
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(data) = serde_json::to_string_pretty(self) {
+            let _ = fs::write(path, data);
+        }
+    }
+
+    /// The streak as of `today`. A streak survives until the end of the day
+    /// after it was last extended, so it isn't lost before you've had a chance
+    /// to practice today.
+    pub fn current_as_of(&self, today: NaiveDate) -> u32 {
+        match self.last_day {
+            Some(last) if last == today || Some(last) == today.checked_sub_days(Days::new(1)) => self.current,
+            _ => 0,
+        }
+    }
+
+    /// Record that the goal was met on `today`. Returns true if the streak changed.
+    pub fn record_goal_met(&mut self, today: NaiveDate) -> bool {
+        if self.last_day == Some(today) {
+            return false;
+        }
+        self.current = self.current_as_of(today) + 1;
+        self.longest = self.longest.max(self.current);
+        self.last_day = Some(today);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, d).unwrap()
+    }
+
+    fn streak(current: u32, last_day: u32) -> StreakData {
+        StreakData { current, longest: current, last_day: Some(day(last_day)) }
+    }
+
+    #[test]
+    fn a_streak_lasts_until_the_end_of_the_next_day() {
+        assert_eq!(streak(4, 10).current_as_of(day(10)), 4);
+        assert_eq!(streak(4, 10).current_as_of(day(11)), 4);
+        assert_eq!(streak(4, 10).current_as_of(day(12)), 0);
+        assert_eq!(StreakData::default().current_as_of(day(10)), 0);
+    }
+
+    #[test]
+    fn meeting_the_goal_twice_in_a_day_counts_once() {
+        let mut streaks = streak(4, 10);
+        assert!(streaks.record_goal_met(day(11)));
+        assert!(!streaks.record_goal_met(day(11)));
+        assert_eq!((streaks.current, streaks.longest, streaks.last_day), (5, 5, Some(day(11))));
+    }
+
+    #[test]
+    fn a_gap_day_restarts_the_streak() {
+        let mut streaks = streak(4, 10);
+        assert!(streaks.record_goal_met(day(13)));
+        assert_eq!((streaks.current, streaks.longest), (1, 4));
+    }
+
+    #[test]
+    fn tests_count_toward_the_local_day_they_were_taken() {
+        let at = |d: u32, h: u32, m: u32| TestResult {
+            timestamp: Local.with_ymd_and_hms(2026, 3, d, h, m, 0).unwrap().with_timezone(&chrono::Utc),
+            duration_secs: 60,
+            ..Default::default()
+        };
+        let results = [at(10, 23, 58), at(11, 0, 1), at(11, 0, 3)];
+        let goal = DailyGoal::Tests { count: 2 };
+        assert!(!goal.progress(&results, day(10)).met);
+        assert!(goal.progress(&results, day(11)).met);
+
+        // Meeting the goal just before and just after midnight extends the streak
+        let mut streaks = StreakData::default();
+        streaks.record_goal_met(results[0].timestamp.with_timezone(&Local).date_naive());
+        streaks.record_goal_met(results[1].timestamp.with_timezone(&Local).date_naive());
+        assert_eq!(streaks.current, 2);
+    }
+}
@@ -0,0 +1,236 @@
+//! Per-key values behind the keyboard heatmap.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::finger_stats::{key_totals, KeyGroup};
+use crate::stats::TestResult;
+
+/// Days counted as "recent" when measuring improvement, unless the settings
+/// say otherwise
+pub const DEFAULT_IMPROVEMENT_DAYS: u32 = 7;
+
+/// What the keyboard colors show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeatmapMode {
+    /// Share of presses that were mistakes
+    #[default]
+    ErrorRate,
+    /// Average time from the previous keystroke
+    Latency,
+    /// How often each key is pressed
+    Frequency,
+    /// Change in error rate: recent tests against earlier ones
+    Improvement,
+}
+
+impl HeatmapMode {
+    pub fn next(&self) -> HeatmapMode {
+        match self {
+            HeatmapMode::ErrorRate => HeatmapMode::Latency,
+            HeatmapMode::Latency => HeatmapMode::Frequency,
+            HeatmapMode::Frequency => HeatmapMode::Improvement,
+            HeatmapMode::Improvement => HeatmapMode::ErrorRate,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HeatmapMode::ErrorRate => "Error rate",
+            HeatmapMode::Latency => "Latency",
+            HeatmapMode::Frequency => "Frequency",
+            HeatmapMode::Improvement => "Improvement",
+        }
+    }
+}
+
+/// Which results the heatmap is built from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeatmapScope {
+    #[default]
+    AllTime,
+    Session,
+}
+
+impl HeatmapScope {
+    pub fn toggle(&self) -> HeatmapScope {
+        match self {
+            HeatmapScope::AllTime => HeatmapScope::Session,
+            HeatmapScope::Session => HeatmapScope::AllTime,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HeatmapScope::AllTime => "all time",
+            HeatmapScope::Session => "this test",
+        }
+    }
+}
+
+/// A value per key, plus the range the colors are scaled over
+pub struct Heatmap {
+    pub mode: HeatmapMode,
+    pub scope: HeatmapScope,
+    values: HashMap<char, f64>,
+    /// Value shown in the coolest color
+    pub low: f64,
+    /// Value shown in the hottest color
+    pub high: f64,
+}
+
+impl Heatmap {
+    /// Build the heatmap for `mode`. `session` is the test in progress (or
+    /// just finished) and `history` every saved result, which must not
+    /// already include it. Improvement compares the last `improvement_days`
+    /// days (at least one) with everything before; for a single test it
+    /// compares the test with the whole history.
+    pub fn new(
+        mode: HeatmapMode,
+        scope: HeatmapScope,
+        session: &TestResult,
+        history: &[TestResult],
+        improvement_days: u32,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let totals = || match scope {
+            HeatmapScope::AllTime => key_totals(history.iter().chain([session])),
+            HeatmapScope::Session => key_totals([session]),
+        };
+
+        let values: HashMap<char, f64> = match mode {
+            HeatmapMode::ErrorRate => per_key(&totals(), error_rate),
+            HeatmapMode::Latency => per_key(&totals(), KeyGroup::average_latency_ms),
+            HeatmapMode::Frequency => per_key(&totals(), |group| (group.presses > 0).then_some(group.presses as f64)),
+            HeatmapMode::Improvement => {
+                let (recent, earlier) = match scope {
+                    HeatmapScope::AllTime => {
+                        let cutoff = now - Duration::days(improvement_days.max(1).into());
+                        let (recent, earlier): (Vec<&TestResult>, Vec<&TestResult>) =
+                            history.iter().chain([session]).partition(|r| r.timestamp >= cutoff);
+                        (key_totals(recent), key_totals(earlier))
+                    }
+                    HeatmapScope::Session => (key_totals([session]), key_totals(history)),
+                };
+                // Positive when the error rate went up
+                per_key(&recent, error_rate)
+                    .into_iter()
+                    .filter_map(|(key, rate)| Some((key, rate - error_rate(earlier.get(&key)?)?)))
+                    .collect()
+            }
+        };
+
+        let (low, high) = match mode {
+            // Centred on "no change" so better and worse read at a glance
+            HeatmapMode::Improvement => {
+                let largest = values.values().map(|v| v.abs()).fold(0.0, f64::max);
+                (-largest, largest)
+            }
+            HeatmapMode::ErrorRate | HeatmapMode::Frequency => (0.0, values.values().copied().fold(0.0, f64::max)),
+            HeatmapMode::Latency => (
+                values.values().copied().fold(f64::INFINITY, f64::min),
+                values.values().copied().fold(0.0, f64::max),
+            ),
+        };
+
+        Heatmap { mode, scope, values, low, high }
+    }
+
+    /// Where a key sits between `low` (0.0) and `high` (1.0), or `None`
+    /// when nothing is recorded for it
+    pub fn heat(&self, key: char) -> Option<f64> {
+        let value = *self.values.get(&key)?;
+        if self.high > self.low {
+            Some(((value - self.low) / (self.high - self.low)).clamp(0.0, 1.0))
+        } else {
+            Some(if self.mode == HeatmapMode::Improvement { 0.5 } else { 0.0 })
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Formatted value for a legend label
+    pub fn format(&self, value: f64) -> String {
+        match self.mode {
+            HeatmapMode::ErrorRate => format!("{:.0}%", value),
+            HeatmapMode::Latency => format!("{:.0}ms", value),
+            HeatmapMode::Frequency => format!("{:.0}", value),
+            HeatmapMode::Improvement => format!("{:+.0}pt", value),
+        }
+    }
+}
+
+fn per_key(totals: &HashMap<char, KeyGroup>, value: impl Fn(&KeyGroup) -> Option<f64>) -> HashMap<char, f64> {
+    totals
+        .iter()
+        .filter_map(|(&key, group)| Some((key, value(group)?)))
+        .collect()
+}
+
+fn error_rate(group: &KeyGroup) -> Option<f64> {
+    group.accuracy().map(|accuracy| 100.0 - accuracy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::KeyStats;
+
+    fn result(days_ago: i64, key: char, presses: usize, errors: usize, now: DateTime<Utc>) -> TestResult {
+        TestResult {
+            timestamp: now - Duration::days(days_ago),
+            char_errors: [(key, errors)].into_iter().filter(|(_, e)| *e > 0).collect(),
+            key_stats: [(key, KeyStats { presses, timed: presses, total_ms: presses as u64 * 100 })].into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn error_rate_is_relative_to_presses() {
+        let now = Utc::now();
+        // 'e' has more errors but is pressed far more often
+        let history = [result(1, 'e', 100, 5, now), result(1, 'q', 10, 2, now)];
+        let heatmap = Heatmap::new(HeatmapMode::ErrorRate, HeatmapScope::AllTime, &TestResult::default(), &history, DEFAULT_IMPROVEMENT_DAYS, now);
+        assert_eq!(heatmap.heat('q'), Some(1.0));
+        assert_eq!(heatmap.heat('e'), Some(0.25));
+        assert_eq!(heatmap.heat('z'), None);
+    }
+
+    #[test]
+    fn session_scope_ignores_history() {
+        let now = Utc::now();
+        let history = [result(1, 'e', 100, 5, now)];
+        let session = result(0, 'a', 10, 0, now);
+        let heatmap = Heatmap::new(HeatmapMode::Frequency, HeatmapScope::Session, &session, &history, DEFAULT_IMPROVEMENT_DAYS, now);
+        assert_eq!(heatmap.heat('a'), Some(1.0));
+        assert_eq!(heatmap.heat('e'), None);
+    }
+
+    #[test]
+    fn improvement_compares_recent_days_with_earlier_ones() {
+        let now = Utc::now();
+        let history = [
+            result(30, 'a', 10, 4, now),
+            result(2, 'a', 10, 1, now),
+            result(30, 's', 10, 1, now),
+            result(2, 's', 10, 3, now),
+        ];
+        let improvement = |days| {
+            Heatmap::new(HeatmapMode::Improvement, HeatmapScope::AllTime, &TestResult::default(), &history, days, now)
+        };
+        let heatmap = improvement(DEFAULT_IMPROVEMENT_DAYS);
+        assert_eq!((heatmap.low, heatmap.high), (-30.0, 30.0));
+        // 'a' went from 40% to 10% errors, 's' from 10% to 30%
+        assert_eq!(heatmap.heat('a'), Some(0.0));
+        assert!(heatmap.heat('s').unwrap() > 0.5);
+        // A longer window counts every test as recent, leaving nothing to compare with
+        assert_eq!(improvement(60).heat('a'), None);
+        // A single day leaves out the tests from two days ago
+        assert_eq!(improvement(1).heat('a'), None);
+    }
+}
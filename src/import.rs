@@ -0,0 +1,543 @@
+//! Import of typing history from ratatap exports and other typing tools.
+//!
+//! Supported sources:
+//!
+//! * **ratatap** - a `ratatap export` JSON document or a raw `progress.json`
+//! * **monkeytype** - the CSV from Monkeytype's "export csv" account option
+//! * **keybr** - the JSON typing data export from keybr.com
+//! * **csv** - any CSV with a header row containing WPM, accuracy and
+//!   timestamp columns (e.g. a ratatap CSV export or a TypeRacer race log)
+//!
+//! Accuracy may be a percentage or a fraction of 1. Which one is decided once
+//! for the whole column, so a real 0.8% is never read as 80%.
+//!
+//! Imported tests are tagged with their source so they can be told apart, and
+//! tests already present in the history are skipped so re-importing the same
+//! file is harmless.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::export::{ExportDocument, JSON_FORMAT_NAME, JSON_FORMAT_VERSION};
+use crate::modes::{Mode, PassageLength};
+use crate::stats::{ProgressData, TestResult};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportSource {
+    Ratatap,
+    Monkeytype,
+    Keybr,
+    Csv,
+}
+
+impl ImportSource {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ImportSource::Ratatap => "ratatap",
+            ImportSource::Monkeytype => "monkeytype",
+            ImportSource::Keybr => "keybr",
+            ImportSource::Csv => "csv",
+        }
+    }
+
+    pub fn all() -> Vec<ImportSource> {
+        vec![
+            ImportSource::Ratatap,
+            ImportSource::Monkeytype,
+            ImportSource::Keybr,
+            ImportSource::Csv,
+        ]
+    }
+
+    pub fn from_name(name: &str) -> Option<ImportSource> {
+        let name = name.to_ascii_lowercase();
+        ImportSource::all().into_iter().find(|s| s.name() == name)
+    }
+
+    /// Guess the source from the file contents
+    fn detect(data: &str) -> Option<ImportSource> {
+        let trimmed = data.trim_start_matches('\u{feff}').trim_start();
+        if trimmed.starts_with('[') {
+            return Some(ImportSource::Keybr);
+        }
+        if trimmed.starts_with('{') {
+            return Some(ImportSource::Ratatap);
+        }
+
+        let header = parse_csv(trimmed).into_iter().next()?.1;
+        let columns: Vec<String> = header.iter().map(|h| h.trim().to_lowercase()).collect();
+        let has = |name: &str| columns.iter().any(|c| c == name);
+        if has("_id") && has("acc") && has("testduration") {
+            Some(ImportSource::Monkeytype)
+        } else {
+            Some(ImportSource::Csv)
+        }
+    }
+}
+
+/// A row that could not be imported
+#[derive(Debug)]
+pub struct SkippedRow {
+    pub row: usize,
+    pub reason: String,
+}
+
+#[derive(Debug)]
+pub struct ImportReport {
+    pub source: ImportSource,
+    pub total: usize,
+    pub imported: usize,
+    pub duplicates: usize,
+    pub skipped: Vec<SkippedRow>,
+}
+
+type ParsedRow = Result<TestResult, SkippedRow>;
+
+/// Import a history file into `progress`. The source is detected from the
+/// contents unless given explicitly. The caller is responsible for saving.
+pub fn import_file(
+    progress: &mut ProgressData,
+    path: &Path,
+    source: Option<ImportSource>,
+) -> Result<ImportReport, String> {
+    let data = fs::read_to_string(path)
+        .map_err(|e| format!("could not read '{}': {}", path.display(), e))?;
+    let source = source
+        .or_else(|| ImportSource::detect(&data))
+        .ok_or_else(|| format!("'{}' is empty", path.display()))?;
+
+    Ok(merge(progress, source, parse(&data, source)?))
+}
+
+fn parse(data: &str, source: ImportSource) -> Result<Vec<ParsedRow>, String> {
+    match source {
+        ImportSource::Ratatap => parse_ratatap(data),
+        ImportSource::Monkeytype => parse_tabular(data, Some("_id"), Some("monkeytype")),
+        ImportSource::Keybr => parse_keybr(data),
+        ImportSource::Csv => parse_tabular(data, None, Some("csv")),
+    }
+}
+
+/// Add the parsed rows that aren't already in `progress`
+fn merge(progress: &mut ProgressData, source: ImportSource, rows: Vec<ParsedRow>) -> ImportReport {
+    let mut report = ImportReport {
+        source,
+        total: rows.len(),
+        imported: 0,
+        duplicates: 0,
+        skipped: Vec::new(),
+    };
+
+    for row in rows {
+        match row {
+            Ok(result) if progress.contains(&result) => report.duplicates += 1,
+            Ok(result) => {
+                progress.results.push(result);
+                report.imported += 1;
+            }
+            Err(skipped) => report.skipped.push(skipped),
+        }
+    }
+
+    progress.results.sort_by_key(|r| r.timestamp);
+    report
+}
+
+fn parse_ratatap(data: &str) -> Result<Vec<ParsedRow>, String> {
+    let value: Value = serde_json::from_str(data).map_err(|e| format!("invalid JSON: {}", e))?;
+
+    if value.get("format").and_then(Value::as_str) == Some(JSON_FORMAT_NAME) {
+        let document: ExportDocument =
+            serde_json::from_value(value).map_err(|e| format!("invalid ratatap export: {}", e))?;
+        if document.version > JSON_FORMAT_VERSION {
+            return Err(format!(
+                "export format version {} is newer than this ratatap supports ({})",
+                document.version, JSON_FORMAT_VERSION
+            ));
+        }
+        return Ok(document
+            .tests
+            .into_iter()
+            .map(|t| {
+                Ok(TestResult {
+                    wpm: t.wpm,
+                    accuracy: t.accuracy,
+                    timestamp: t.timestamp,
+                    duration_secs: t.duration_secs,
+                    char_errors: t.char_errors.into_iter().map(|c| (c.char, c.count)).collect(),
+                    mode: t.mode.as_deref().and_then(Mode::from_name),
+                    length: t.length.as_deref().and_then(PassageLength::from_name),
+                    input_mode: t.input_mode,
+                    word_accuracy: t.word_accuracy,
+                    seed: t.seed,
+                    language: t.language,
+                    word_options: t.word_options,
+                    custom_passage: t.custom_passage,
+                    retry_of: t.retry_of,
+                    // Tests ratatap had imported itself keep their original source
+                    source: Some(t.source.unwrap_or_else(|| ImportSource::Ratatap.name().to_string())),
+                    import_id: t.import_id,
+                    ..Default::default()
+                })
+            })
+            .collect());
+    }
+
+    let progress: ProgressData = serde_json::from_value(value)
+        .map_err(|_| "not a ratatap export or progress file".to_string())?;
+    Ok(progress
+        .results
+        .into_iter()
+        .map(|mut result| {
+            result.source.get_or_insert_with(|| ImportSource::Ratatap.name().to_string());
+            Ok(result)
+        })
+        .collect())
+}
+
+fn parse_keybr(data: &str) -> Result<Vec<ParsedRow>, String> {
+    let value: Value = serde_json::from_str(data).map_err(|e| format!("invalid JSON: {}", e))?;
+    let entries = value
+        .as_array()
+        .ok_or("expected a JSON array of keybr results")?;
+
+    Ok(entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let row = i + 1;
+            let skip = |reason: &str| SkippedRow { row, reason: reason.to_string() };
+
+            let timestamp = entry
+                .get("timeStamp")
+                .and_then(Value::as_str)
+                .and_then(parse_timestamp)
+                .ok_or_else(|| skip("missing or invalid timeStamp"))?;
+            // keybr reports speed in characters per minute
+            let speed = entry
+                .get("speed")
+                .and_then(Value::as_f64)
+                .ok_or_else(|| skip("missing speed"))?;
+            let length = entry.get("length").and_then(Value::as_f64).unwrap_or(0.0);
+            let errors = entry.get("errors").and_then(Value::as_f64).unwrap_or(0.0);
+            let time_ms = entry.get("time").and_then(Value::as_f64).unwrap_or(0.0);
+            let accuracy = if length > 0.0 {
+                ((length - errors) / length * 100.0).clamp(0.0, 100.0)
+            } else {
+                100.0
+            };
+
+            Ok(TestResult {
+                wpm: speed / 5.0,
+                accuracy,
+                timestamp,
+                duration_secs: (time_ms / 1000.0).round() as u64,
+                source: Some(ImportSource::Keybr.name().to_string()),
+                ..Default::default()
+            })
+        })
+        .collect())
+}
+
+const WPM_COLUMNS: &[&str] = &["wpm", "net wpm", "speed (wpm)"];
+const ACCURACY_COLUMNS: &[&str] = &["accuracy", "acc", "accuracy (%)"];
+const TIMESTAMP_COLUMNS: &[&str] = &["timestamp", "date/time (utc)", "datetime", "date", "time stamp"];
+const DURATION_COLUMNS: &[&str] = &["duration_secs", "testduration", "duration", "seconds", "time (s)"];
+
+/// Parse a CSV with a header row, locating columns by common names
+fn parse_tabular(data: &str, id_column: Option<&str>, source: Option<&str>) -> Result<Vec<ParsedRow>, String> {
+    let mut rows = parse_csv(data.trim_start_matches('\u{feff}')).into_iter();
+    let (_, header) = rows.next().ok_or("the file is empty")?;
+    let columns: HashMap<String, usize> = header
+        .iter()
+        .enumerate()
+        .map(|(i, h)| (h.trim().to_lowercase(), i))
+        .collect();
+    let find = |names: &[&str]| names.iter().find_map(|n| columns.get(*n).copied());
+
+    let wpm_col = find(WPM_COLUMNS).ok_or("no WPM column found in the header")?;
+    let accuracy_col = find(ACCURACY_COLUMNS).ok_or("no accuracy column found in the header")?;
+    let timestamp_col = find(TIMESTAMP_COLUMNS).ok_or("no timestamp or date column found in the header")?;
+    let duration_col = find(DURATION_COLUMNS);
+    // A ratatap CSV export carries the source and id of tests it had imported
+    let id_col = id_column.or(Some("import_id")).and_then(|name| columns.get(name).copied());
+    let source_col = columns.get("source").copied();
+
+    let rows: Vec<_> = rows.filter(|(_, fields)| fields.iter().any(|f| !f.trim().is_empty())).collect();
+    let accuracies = rows.iter().map(|(_, fields)| fields.get(accuracy_col).map_or("", String::as_str));
+    let accuracy_scale = accuracy_scale(&header[accuracy_col], accuracies);
+
+    Ok(rows
+        .into_iter()
+        .map(|(row, fields)| {
+            let skip = |reason: String| SkippedRow { row, reason };
+            let field = |col: usize| fields.get(col).map(|f| f.trim()).unwrap_or("");
+
+            let wpm = parse_number(field(wpm_col))
+                .ok_or_else(|| skip(format!("invalid WPM '{}'", field(wpm_col))))?;
+            let accuracy = parse_number(field(accuracy_col))
+                .map(|a| a * accuracy_scale)
+                .filter(|a| (0.0..=100.0).contains(a))
+                .ok_or_else(|| skip(format!("invalid accuracy '{}'", field(accuracy_col))))?;
+            let timestamp = parse_timestamp(field(timestamp_col))
+                .ok_or_else(|| skip(format!("invalid timestamp '{}'", field(timestamp_col))))?;
+            let duration_secs = duration_col
+                .and_then(|col| parse_number(field(col)))
+                .map(|d| d.max(0.0).round() as u64)
+                .unwrap_or(0);
+
+            Ok(TestResult {
+                wpm,
+                accuracy,
+                timestamp,
+                duration_secs,
+                source: source_col
+                    .map(field)
+                    .filter(|s| !s.is_empty())
+                    .or(source)
+                    .map(str::to_string),
+                import_id: id_col.map(field).filter(|id| !id.is_empty()).map(str::to_string),
+                ..Default::default()
+            })
+        })
+        .collect())
+}
+
+/// What to multiply the accuracy column by to get a percentage. A column is
+/// read as fractions only when nothing marks it as a percentage: no `%` in
+/// the header or the values, and no value above 1.
+fn accuracy_scale<'a>(header: &str, values: impl Iterator<Item = &'a str>) -> f64 {
+    let mut values = values.map(str::trim).filter(|v| !v.is_empty()).peekable();
+    if header.contains('%') || values.peek().is_none() {
+        return 1.0;
+    }
+    let fractions = values.all(|v| !v.ends_with('%') && parse_number(v).is_none_or(|a| a <= 1.0));
+    if fractions { 100.0 } else { 1.0 }
+}
+
+fn parse_number(field: &str) -> Option<f64> {
+    field.trim_end_matches('%').trim().parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+/// Accepts RFC 3339, `YYYY-MM-DD HH:MM:SS` (UTC), plain dates, and Unix
+/// timestamps in seconds or milliseconds.
+fn parse_timestamp(field: &str) -> Option<DateTime<Utc>> {
+    if let Ok(number) = field.parse::<i64>() {
+        // Anything past the year 5138 in seconds is really milliseconds
+        return if number > 100_000_000_000 {
+            Utc.timestamp_millis_opt(number).single()
+        } else {
+            Utc.timestamp_opt(number, 0).single()
+        };
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(field) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(field, format) {
+            return Some(dt.and_utc());
+        }
+    }
+    NaiveDate::parse_from_str(field, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc())
+}
+
+/// Minimal RFC 4180 parser. Returns each record with its 1-based line number.
+fn parse_csv(data: &str) -> Vec<(usize, Vec<String>)> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = data.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                fields.push(std::mem::take(&mut field));
+                records.push((record_line, std::mem::take(&mut fields)));
+                line += 1;
+                record_line = line;
+            }
+            '\n' => {
+                field.push(c);
+                line += 1;
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push((record_line, fields));
+    }
+
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::{export, ExportFilter, ExportFormat};
+
+    fn imported(data: &str, source: ImportSource) -> Vec<TestResult> {
+        parse(data, source).unwrap().into_iter().map(|row| row.ok().unwrap()).collect()
+    }
+
+    #[test]
+    fn csv_fields_can_hold_quotes_commas_and_newlines() {
+        let records = parse_csv("a,\"b, \"\"c\"\"\",d\r\n\"multi\nline\",e,\n\nlast");
+        assert_eq!(
+            records,
+            [
+                (1, vec!["a".to_string(), "b, \"c\"".to_string(), "d".to_string()]),
+                (2, vec!["multi\nline".to_string(), "e".to_string(), String::new()]),
+                (4, vec![String::new()]),
+                (5, vec!["last".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn timestamps_in_every_supported_format() {
+        let noon = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        for field in [
+            "2026-03-01T12:00:00Z",
+            "2026-03-01T13:00:00+01:00",
+            "2026-03-01 12:00:00",
+            "2026-03-01T12:00:00",
+            "2026-03-01 12:00",
+            "1772366400",
+            "1772366400000",
+        ] {
+            assert_eq!(parse_timestamp(field), Some(noon), "{}", field);
+        }
+        assert_eq!(parse_timestamp("2026-03-01"), Some(noon - chrono::Duration::hours(12)));
+        assert_eq!(parse_timestamp("01/03/2026"), None);
+        assert_eq!(parse_timestamp(""), None);
+    }
+
+    #[test]
+    fn detects_the_source_from_the_contents() {
+        assert_eq!(ImportSource::detect("\u{feff} [{\"speed\": 300}]"), Some(ImportSource::Keybr));
+        assert_eq!(ImportSource::detect("{\"results\": []}"), Some(ImportSource::Ratatap));
+        assert_eq!(
+            ImportSource::detect("_id,isPb,wpm,acc,testDuration,timestamp\n"),
+            Some(ImportSource::Monkeytype)
+        );
+        assert_eq!(ImportSource::detect("wpm,accuracy,date\n"), Some(ImportSource::Csv));
+        assert_eq!(ImportSource::detect("  \n"), None);
+    }
+
+    #[test]
+    fn parses_a_monkeytype_export() {
+        let data = "_id,isPb,wpm,acc,rawWpm,testDuration,timestamp\n\
+                    abc1,true,82.5,97.1,85,30.02,1772366400000\n\
+                    abc2,false,oops,95,80,15,1772366500000\n";
+        let rows = parse(data, ImportSource::Monkeytype).unwrap();
+        let first = rows[0].as_ref().ok().unwrap();
+        assert_eq!((first.wpm, first.accuracy, first.duration_secs), (82.5, 97.1, 30));
+        assert_eq!(first.import_id.as_deref(), Some("abc1"));
+        assert_eq!(first.source.as_deref(), Some("monkeytype"));
+        let skipped = rows[1].as_ref().err().unwrap();
+        assert_eq!((skipped.row, skipped.reason.as_str()), (3, "invalid WPM 'oops'"));
+    }
+
+    #[test]
+    fn parses_a_keybr_export() {
+        let data = r#"[
+            {"timeStamp": "2026-03-01T12:00:00Z", "length": 200, "time": 40000, "errors": 10, "speed": 300},
+            {"length": 100, "speed": 250}
+        ]"#;
+        let rows = parse(data, ImportSource::Keybr).unwrap();
+        let first = rows[0].as_ref().ok().unwrap();
+        assert_eq!((first.wpm, first.accuracy, first.duration_secs), (60.0, 95.0, 40));
+        assert_eq!(first.source.as_deref(), Some("keybr"));
+        assert_eq!(rows[1].as_ref().err().unwrap().reason, "missing or invalid timeStamp");
+    }
+
+    #[test]
+    fn accuracy_scale_is_decided_per_column() {
+        // A percent column with a sub-1% value stays a percentage
+        let percent = imported("wpm,accuracy,date\n40,0.5,2026-03-01\n50,96,2026-03-02\n", ImportSource::Csv);
+        assert_eq!(percent.iter().map(|r| r.accuracy).collect::<Vec<_>>(), [0.5, 96.0]);
+
+        let fractions = imported("wpm,accuracy,date\n40,0.5,2026-03-01\n50,0.96,2026-03-02\n", ImportSource::Csv);
+        assert_eq!(fractions.iter().map(|r| r.accuracy).collect::<Vec<_>>(), [50.0, 96.0]);
+
+        // A % in the header or the values rules out fractions
+        let header = imported("wpm,accuracy (%),date\n40,0.5,2026-03-01\n", ImportSource::Csv);
+        assert_eq!(header[0].accuracy, 0.5);
+        let values = imported("wpm,accuracy,date\n40,0.5%,2026-03-01\n50,0.9,2026-03-02\n", ImportSource::Csv);
+        assert_eq!(values.iter().map(|r| r.accuracy).collect::<Vec<_>>(), [0.5, 0.9]);
+    }
+
+    #[test]
+    fn ratatap_rows_are_tagged_with_their_source() {
+        let export = r#"{"format": "ratatap-history", "version": 1, "exported_at": "2026-03-01T12:00:00Z",
+            "tests": [{"timestamp": "2026-03-01T12:00:00Z", "mode": "normal", "length": "short",
+                       "input_mode": "normal", "seed": 3, "retry_of": null, "wpm": 70.0, "accuracy": 98.0,
+                       "word_accuracy": null, "duration_secs": 20, "errors": 0, "char_errors": []}]}"#;
+        assert_eq!(imported(export, ImportSource::Ratatap)[0].source.as_deref(), Some("ratatap"));
+
+        // A progress file keeps the source of tests it had imported itself
+        let progress = r#"{"results": [
+            {"wpm": 70.0, "accuracy": 98.0, "timestamp": "2026-03-01T12:00:00Z", "duration_secs": 20},
+            {"wpm": 60.0, "accuracy": 97.0, "timestamp": "2026-03-02T12:00:00Z", "duration_secs": 20,
+             "source": "keybr"}
+        ]}"#;
+        let sources: Vec<_> = imported(progress, ImportSource::Ratatap).into_iter().map(|r| r.source).collect();
+        assert_eq!(sources, [Some("ratatap".to_string()), Some("keybr".to_string())]);
+    }
+
+    #[test]
+    fn exported_imports_keep_their_source_and_id() {
+        let data = "_id,wpm,acc,testDuration,timestamp\na,80,97,30,1772366400000\n";
+        let mut progress = ProgressData { results: Vec::new() };
+        merge(&mut progress, ImportSource::Monkeytype, parse(data, ImportSource::Monkeytype).unwrap());
+
+        for (format, source) in [(ExportFormat::Json, ImportSource::Ratatap), (ExportFormat::Csv, ImportSource::Csv)] {
+            let (exported, _) = export(&progress, format, &ExportFilter::default());
+            let rows = imported(&exported, source);
+            assert_eq!(rows[0].source.as_deref(), Some("monkeytype"), "{:?}", format);
+            assert_eq!(rows[0].import_id.as_deref(), Some("a"), "{:?}", format);
+
+            // So importing the export into the same history adds nothing
+            let mut again = ProgressData { results: progress.results.clone() };
+            let report = merge(&mut again, source, parse(&exported, source).unwrap());
+            assert_eq!((report.imported, report.duplicates), (0, 1), "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn importing_the_same_file_twice_adds_nothing() {
+        let data = "_id,wpm,acc,testDuration,timestamp\n\
+                    a,80,97,30,1772366400000\n\
+                    b,81,96,30,1772366500000\n";
+        let mut progress = ProgressData { results: Vec::new() };
+        let first = merge(&mut progress, ImportSource::Monkeytype, parse(data, ImportSource::Monkeytype).unwrap());
+        assert_eq!((first.total, first.imported, first.duplicates), (2, 2, 0));
+
+        let again = merge(&mut progress, ImportSource::Monkeytype, parse(data, ImportSource::Monkeytype).unwrap());
+        assert_eq!((again.imported, again.duplicates), (0, 2));
+        assert_eq!(progress.results.len(), 2);
+
+        // Without an id, tests match on their time and speed
+        let csv = "wpm,accuracy,timestamp\n80,97,1772366400\n90,97,1772366400\n";
+        let report = merge(&mut progress, ImportSource::Csv, parse(csv, ImportSource::Csv).unwrap());
+        assert_eq!((report.imported, report.duplicates), (1, 1));
+    }
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+/// How strictly typed characters are checked against the passage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputMode {
+    /// Wrong characters are accepted and the cursor moves on
+    #[default]
+    Normal,
+    /// The cursor won't advance until the correct key is pressed
+    StopOnLetter,
+    /// Space won't advance past a word until the word is typed correctly
+    StopOnWord,
+    /// Every error must be fixed before the test can finish
+    MustCorrect,
+}
+
+impl InputMode {
+    pub fn next(&self) -> InputMode {
+        match self {
+            InputMode::Normal => InputMode::StopOnLetter,
+            InputMode::StopOnLetter => InputMode::StopOnWord,
+            InputMode::StopOnWord => InputMode::MustCorrect,
+            InputMode::MustCorrect => InputMode::Normal,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            InputMode::Normal => "Normal",
+            InputMode::StopOnLetter => "Stop on Letter",
+            InputMode::StopOnWord => "Stop on Word",
+            InputMode::MustCorrect => "Must Correct",
+        }
+    }
+
+    pub fn slug(&self) -> &'static str {
+        match self {
+            InputMode::Normal => "normal",
+            InputMode::StopOnLetter => "stop_on_letter",
+            InputMode::StopOnWord => "stop_on_word",
+            InputMode::MustCorrect => "must_correct",
+        }
+    }
+
+    /// Strict modes count every wrong keystroke against accuracy, since the
+    /// passage itself always ends up correct
+    pub fn is_strict(&self) -> bool {
+        *self != InputMode::Normal
+    }
+}
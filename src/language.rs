@@ -0,0 +1,201 @@
+//! Word lists used to generate practice passages.
+//!
+//! A handful of lists ship with the binary; the header of each says how it
+//! was put together. More can be added by dropping files into
+//! `~/.ratatap/languages/`:
+//!
+//! * `<name>.txt` — one word per line (or whitespace separated), most
+//!   frequent first; lines starting with `#` are ignored, except for a
+//!   `# ranked: N` line saying only the first N words are in frequency order
+//! * `<name>.json` — a Monkeytype-style `{"name": ..., "words": [...]}` file
+//!
+//! A user list with the same name as a bundled one replaces it.
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_LANGUAGE: &str = "english";
+
+const BUNDLED: &[(&str, &str)] = &[
+    ("english", include_str!("../assets/languages/english.txt")),
+    ("english_1k", include_str!("../assets/languages/english_1k.txt")),
+    ("french", include_str!("../assets/languages/french.txt")),
+    ("german", include_str!("../assets/languages/german.txt")),
+    ("italian", include_str!("../assets/languages/italian.txt")),
+    ("polish", include_str!("../assets/languages/polish.txt")),
+    ("portuguese", include_str!("../assets/languages/portuguese.txt")),
+    ("spanish", include_str!("../assets/languages/spanish.txt")),
+];
+
+/// Where a word list comes from
+#[derive(Debug)]
+enum Source {
+    Bundled(&'static str),
+    Text(PathBuf),
+    Json(PathBuf),
+}
+
+/// A named word list. Lists start with common words, but only the first
+/// `ranked` are known to be in frequency order.
+#[derive(Debug, Clone)]
+pub struct Language {
+    pub name: String,
+    pub words: Vec<String>,
+    /// How many words at the start of the list are in frequency order. The
+    /// rest are in no particular order.
+    pub ranked: usize,
+}
+
+#[derive(Deserialize)]
+struct MonkeytypeList {
+    words: Vec<String>,
+}
+
+impl Default for Language {
+    fn default() -> Self {
+        let (name, data) = BUNDLED
+            .iter()
+            .find(|(name, _)| *name == DEFAULT_LANGUAGE)
+            .expect("default language is bundled");
+        let words = parse_words(data);
+        Language {
+            name: name.to_string(),
+            ranked: parse_ranked(data).unwrap_or(words.len()),
+            words,
+        }
+    }
+}
+
+impl Language {
+    /// Load a list by name, preferring a user file over a bundled list
+    pub fn load(name: &str) -> Result<Self, String> {
+        let key = name.to_lowercase();
+        let Some(source) = sources().remove(&key) else {
+            return Err(format!(
+                "unknown language '{}' (expected one of: {})",
+                name,
+                available().join(", ")
+            ));
+        };
+
+        let (words, ranked) = match &source {
+            Source::Bundled(data) => (parse_words(data), parse_ranked(data)),
+            Source::Text(path) => {
+                let data = read(path)?;
+                (parse_words(&data), parse_ranked(&data))
+            }
+            Source::Json(path) => {
+                let list: MonkeytypeList = serde_json::from_str(&read(path)?)
+                    .map_err(|e| format!("could not parse '{}': {}", path.display(), e))?;
+                (dedup(list.words.iter().flat_map(|w| w.split_whitespace())), None)
+            }
+        };
+
+        if words.is_empty() {
+            return Err(format!("language '{}' does not contain any words", key));
+        }
+
+        let ranked = ranked.unwrap_or(words.len()).min(words.len());
+        Ok(Language { name: key, words, ranked })
+    }
+
+    /// Human-readable name, e.g. `english_1k` -> `English 1k`
+    pub fn display_name(&self) -> String {
+        display_name(&self.name)
+    }
+}
+
+/// Names of every bundled and user-provided list, sorted
+pub fn available() -> Vec<String> {
+    sources().into_keys().collect()
+}
+
+pub fn display_name(name: &str) -> String {
+    let spaced = name.replace(['_', '-'], " ");
+    let mut chars = spaced.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => spaced,
+    }
+}
+
+fn user_dir() -> PathBuf {
+    let mut path = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push(".ratatap");
+    path.push("languages");
+    path
+}
+
+fn sources() -> BTreeMap<String, Source> {
+    let mut sources: BTreeMap<String, Source> = BUNDLED
+        .iter()
+        .map(|(name, data)| (name.to_string(), Source::Bundled(data)))
+        .collect();
+
+    if let Ok(entries) = fs::read_dir(user_dir()) {
+        for path in entries.flatten().map(|e| e.path()) {
+            let Some(stem) = path.file_stem().map(|s| s.to_string_lossy().to_lowercase()) else {
+                continue;
+            };
+            let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase());
+            match extension.as_deref() {
+                Some("txt") => {
+                    sources.insert(stem, Source::Text(path));
+                }
+                Some("json") => {
+                    sources.insert(stem, Source::Json(path));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    sources
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("could not read '{}': {}", path.display(), e))
+}
+
+fn parse_words(data: &str) -> Vec<String> {
+    dedup(
+        data.lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .flat_map(str::split_whitespace),
+    )
+}
+
+/// The N of a `# ranked: N` header line
+fn parse_ranked(data: &str) -> Option<usize> {
+    data.lines()
+        .filter_map(|line| line.trim_start().strip_prefix('#'))
+        .find_map(|comment| comment.trim().strip_prefix("ranked:"))
+        .and_then(|n| n.trim().parse().ok())
+}
+
+/// Keep the first occurrence of each word so frequency order is preserved
+fn dedup<'a>(words: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    words
+        .filter(|w| seen.insert(*w))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_ranked_part_of_a_list_counts_as_ranked() {
+        assert_eq!(parse_ranked("# English\n# ranked: 100\nthe\nbe"), Some(100));
+        assert_eq!(parse_ranked("# ranked words follow\nthe"), None);
+        assert_eq!(parse_ranked("the be to"), None);
+
+        let english = Language::load("english_1k").unwrap();
+        assert_eq!((english.words.len(), english.ranked), (1_000, 100));
+        assert_eq!(Language::load("french").unwrap().ranked, 0);
+    }
+}
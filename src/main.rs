@@ -1,91 +1,311 @@
+mod achievements;
+mod cli;
+mod export;
 mod finger_map;
+mod finger_stats;
+mod goals;
+mod heatmap;
+mod import;
+mod input_mode;
+mod language;
 mod modes;
+mod plain;
+mod settings;
 mod stats;
+mod typing;
 mod ui;
 
 use crossterm::{
+    cursor::SetCursorStyle,
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Alignment, Constraint, Direction, Layout},
+    layout::{Alignment, Rect},
     style::Style,
     text::{Line, Span},
     widgets::{Block, BorderType, Borders, Paragraph, Wrap},
     Frame, Terminal,
 };
 use std::{
-    collections::HashMap,
+    cell::RefCell,
     error::Error,
     io,
     time::{Duration, Instant},
 };
 
-use chrono::Utc;
+use achievements::AchievementData;
+use chrono::{DateTime, Local, Utc};
+use cli::{Command, RunOptions};
 use finger_map::Finger;
-use modes::{Mode, PassageLength};
-use stats::{ProgressData, TestResult};
+use goals::StreakData;
+use input_mode::InputMode;
+use language::Language;
+use modes::{
+    code::{PassageCode, SeedEntry},
+    custom::CustomPassage,
+    random_seed,
+    words::{Difficulty, WordOptions},
+    Mode, PassageLength,
+};
+use settings::Settings;
+use finger_stats::FingerReport;
+use heatmap::{Heatmap, HeatmapMode, HeatmapScope};
+use stats::{PersonalBest, ProgressData, RecordKey, TestResult};
+use typing::TypingSession;
 use ui::{
+    achievements::render_achievements,
     charts::{render_inline_progress, render_wpm_sparkline},
+    color_depth::ColorDepth,
+    records::render_personal_bests,
+    export_menu::{render_export_menu, ExportMenu},
+    finger_stats::render_finger_stats,
     keyboard::render_keyboard,
-    theme::{Theme, correct_char_style, incorrect_char_style, current_char_style, pending_char_style, subtitle_style, wpm_color, interpolate_color},
+    caret::{self, CaretStyle, SmoothCaret},
+    panels::{self, LayoutPreset},
+    passage::{passage_height, render_passage, Markup, PassageLayout},
+    language_menu::{render_language_menu, LanguageMenu},
+    toast::{render_toasts, Toast},
+    word_menu::{render_word_menu, WordMenu},
+    theme::{Theme, subtitle_style, wpm_color, interpolate_color},
 };
 use ratatui::style::Modifier;
 
+/// Longest seed or passage code the seed prompt takes
+const MAX_CODE_LENGTH: usize = 80;
+
+/// What the heatmap shows and the data it was built from. The heatmap is
+/// rebuilt when any of these change, rather than on every frame.
+#[derive(PartialEq)]
+struct HeatmapStamp {
+    mode: HeatmapMode,
+    scope: HeatmapScope,
+    results: usize,
+    finished: bool,
+    /// Keystrokes of the test in progress
+    presses: usize,
+    errors: usize,
+}
+
 struct App {
     mode: Mode,
-    target_text: String,
-    typed_text: String,
+    session: TypingSession,
+    /// Line breaks of the passage, worked out when it is first drawn
+    passage_layout: RefCell<PassageLayout>,
+    /// Where the smooth caret has got to
+    caret: SmoothCaret,
+    /// Keyboard heatmap and what it was built from
+    heatmap: Option<(HeatmapStamp, Heatmap)>,
     start_time: Option<Instant>,
     end_time: Option<Instant>,
-    char_errors: HashMap<char, usize>,
     progress: ProgressData,
     current_wpm: f64,
     current_accuracy: f64,
+    word_accuracy: f64,
     should_quit: bool,
     show_reset_confirmation: bool,
+    export_menu: Option<ExportMenu>,
+    language_menu: Option<LanguageMenu>,
+    word_menu: Option<WordMenu>,
+    /// Seed or passage code typed so far into the seed prompt, while it is open
+    seed_input: Option<String>,
+    show_personal_bests: bool,
+    show_finger_stats: bool,
+    new_personal_best: Option<PersonalBest>,
     theme: Theme,
+    /// Colors the terminal can show
+    color_depth: ColorDepth,
+    layout: LayoutPreset,
     passage_length: PassageLength,
+    custom_passage: Option<CustomPassage>,
+    language: Language,
+    word_options: WordOptions,
+    /// Seed the current passage was generated from
+    seed: u64,
+    /// When the current passage was first completed, once it has been
+    first_attempt: Option<DateTime<Utc>>,
+    settings: Settings,
+    streaks: StreakData,
+    achievements: AchievementData,
+    show_achievements: bool,
+    toasts: Vec<Toast>,
 }
 
 impl App {
-    fn new() -> Self {
+    fn new(options: RunOptions, custom_passage: Option<CustomPassage>, language: Option<Language>) -> Self {
         let progress = ProgressData::load();
-        let mode = Mode::Normal;
-        let passage_length = PassageLength::default();
-        let target_text = mode.generate_text(&progress, passage_length.word_count());
+        let settings = Settings::load();
+        let code = match &options.seed {
+            Some(SeedEntry::Code(code)) => Some(code),
+            _ => None,
+        };
+        let mode = code.map(|c| c.mode).or(options.mode).unwrap_or(Mode::Normal);
+        let passage_length = code.map(|c| c.length).or(options.length).unwrap_or_default();
+        // A saved list may since have been removed from ~/.ratatap/languages
+        let language = language
+            .unwrap_or_else(|| Language::load(&settings.language).unwrap_or_default());
+        let mut word_options = code.map_or(settings.words, |c| c.words);
+        if let Some(difficulty) = options.difficulty {
+            word_options.difficulty = difficulty;
+        }
+        let seed = match &options.seed {
+            Some(SeedEntry::Seed(seed)) => *seed,
+            Some(SeedEntry::Code(code)) => code.seed,
+            None => random_seed(),
+        };
+        let target_text = match &custom_passage {
+            Some(passage) => passage.text.clone(),
+            None => mode.generate_text(
+                &progress,
+                &language,
+                &word_options,
+                passage_length.word_count(),
+                seed,
+            ),
+        };
 
-        App {
+        let mut app = App {
             mode,
-            target_text,
-            typed_text: String::new(),
+            session: TypingSession::new(&target_text, settings.input_mode),
+            passage_layout: RefCell::default(),
+            caret: SmoothCaret::default(),
+            heatmap: None,
             start_time: None,
             end_time: None,
-            char_errors: HashMap::new(),
             progress,
             current_wpm: 0.0,
             current_accuracy: 0.0,
+            word_accuracy: 0.0,
             should_quit: false,
             show_reset_confirmation: false,
-            theme: Theme::load(),
+            export_menu: None,
+            language_menu: None,
+            word_menu: None,
+            seed_input: None,
+            show_personal_bests: false,
+            show_finger_stats: false,
+            new_personal_best: None,
+            theme: options.theme.unwrap_or_else(Theme::load),
+            color_depth: options.colors.or(settings.colors).unwrap_or_else(ColorDepth::detect),
+            layout: options.layout.unwrap_or(settings.layout),
             passage_length,
+            custom_passage,
+            language,
+            word_options,
+            seed,
+            first_attempt: None,
+            settings,
+            streaks: StreakData::load(),
+            achievements: AchievementData::load(),
+            show_achievements: false,
+            toasts: Vec::new(),
+        };
+        // The goal may have been met (or changed) since the last session
+        app.update_streak();
+        // History may have been imported since the last session. Those
+        // achievements were earned back then, so they unlock without a toast.
+        if !app.achievements.evaluate(&app.progress, &app.streaks, &app.settings.daily_goal).is_empty() {
+            app.achievements.save();
         }
+        app
+    }
+
+    /// Whether a panel or dialog is open over the passage
+    fn has_overlay(&self) -> bool {
+        self.show_achievements
+            || self.show_personal_bests
+            || self.show_finger_stats
+            || self.show_reset_confirmation
+            || self.export_menu.is_some()
+            || self.seed_input.is_some()
+            || self.word_menu.is_some()
+            || self.language_menu.is_some()
     }
 
     fn reset_test(&mut self) {
-        self.target_text = self.mode.generate_text(&self.progress, self.passage_length.word_count());
-        self.typed_text = String::new();
+        self.load_passage(random_seed());
+    }
+
+    /// Start a new test on the passage generated from `seed`
+    fn load_passage(&mut self, seed: u64) {
+        self.seed = seed;
+        self.first_attempt = None;
+        let target_text = match &self.custom_passage {
+            Some(passage) => passage.text.clone(),
+            None => self.mode.generate_text(
+                &self.progress,
+                &self.language,
+                &self.word_options,
+                self.passage_length.word_count(),
+                seed,
+            ),
+        };
+        self.start_session(&target_text);
+    }
+
+    /// Switch to the passage a shared code describes. The code's mode and
+    /// options last for this session; the saved settings are left alone.
+    fn load_code(&mut self, code: PassageCode) -> Result<(), String> {
+        if code.language != self.language.name {
+            self.language = Language::load(&code.language)?;
+        }
+        self.mode = code.mode;
+        self.passage_length = code.length;
+        self.word_options = code.words;
+        self.custom_passage = None;
+        self.load_passage(code.seed);
+        Ok(())
+    }
+
+    /// Code to share the current passage by, when it can be shared
+    fn passage_code(&self) -> Option<PassageCode> {
+        if self.custom_passage.is_some() {
+            return None;
+        }
+        PassageCode::new(self.mode, self.passage_length, &self.language.name, self.word_options, self.seed)
+    }
+
+    fn show_caret_toast(&mut self) {
+        let motion = if self.settings.smooth_caret { "smooth" } else { "jumps" };
+        self.toasts.retain(|toast| toast.title != "Caret");
+        self.toasts.push(Toast::new("Caret", format!("{} ({})", self.settings.caret.name(), motion)));
+    }
+
+    /// Record key tests taken with the current settings count towards
+    fn record_key(&self) -> RecordKey {
+        RecordKey {
+            mode: self.mode,
+            length: self.passage_length,
+            input_mode: self.settings.input_mode,
+            language: Some(self.language.name.clone()),
+            difficulty: RecordKey::difficulty(self.mode, Some(self.word_options)),
+        }
+    }
+
+    /// Type the passage just finished again, as a linked retry
+    fn retry_test(&mut self) {
+        // Weak-letter text depends on the history that just changed, so reuse the text itself
+        let text = self.session.text();
+        self.start_session(&text);
+    }
+
+    fn start_session(&mut self, text: &str) {
+        self.session = TypingSession::new(text, self.settings.input_mode);
+        self.passage_layout = RefCell::default();
+        self.caret = SmoothCaret::default();
         self.start_time = None;
         self.end_time = None;
-        self.char_errors = HashMap::new();
+        self.new_personal_best = None;
         self.current_wpm = 0.0;
         self.current_accuracy = 0.0;
+        self.word_accuracy = 0.0;
     }
 
     fn change_mode(&mut self, new_mode: Mode) {
         self.mode = new_mode;
+        self.custom_passage = None;
         self.reset_test();
     }
 
@@ -97,6 +317,8 @@ impl App {
                     // Clear history and reset
                     let _ = ProgressData::clear_history();
                     self.progress = ProgressData::load();
+                    self.streaks = StreakData::default();
+                    self.streaks.save();
                     self.reset_test();
                     self.show_reset_confirmation = false;
                 }
@@ -109,6 +331,123 @@ impl App {
             return;
         }
 
+        if let Some(menu) = &mut self.export_menu {
+            match key {
+                KeyCode::Up => menu.select_previous(),
+                KeyCode::Down => menu.select_next(),
+                KeyCode::Left => menu.cycle(false),
+                KeyCode::Right => menu.cycle(true),
+                KeyCode::Enter => menu.export(&self.progress),
+                KeyCode::Char(c) => menu.type_char(c),
+                KeyCode::Backspace => menu.backspace(),
+                KeyCode::Esc => self.export_menu = None,
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(menu) = &mut self.language_menu {
+            match key {
+                KeyCode::Up => menu.select_previous(),
+                KeyCode::Down => menu.select_next(),
+                KeyCode::Enter => {
+                    if let Some(language) = menu.load_selected() {
+                        self.settings.language = language.name.clone();
+                        self.settings.save();
+                        self.language = language;
+                        self.language_menu = None;
+                        self.custom_passage = None;
+                        self.reset_test();
+                    }
+                }
+                KeyCode::Esc => self.language_menu = None,
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(input) = &mut self.seed_input {
+            match key {
+                KeyCode::Char(c) if !c.is_whitespace() && input.chars().count() < MAX_CODE_LENGTH => {
+                    input.push(c)
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => {
+                    // Keep the prompt open on a bad seed so it can be corrected
+                    let loaded = SeedEntry::parse(input).and_then(|entry| match entry {
+                        SeedEntry::Seed(seed) => {
+                            self.custom_passage = None;
+                            self.load_passage(seed);
+                            Ok(())
+                        }
+                        SeedEntry::Code(code) => self.load_code(*code),
+                    });
+                    match loaded {
+                        Ok(()) => self.seed_input = None,
+                        Err(err) => {
+                            self.toasts.retain(|toast| toast.title != "Seed not loaded");
+                            self.toasts.push(Toast::new("Seed not loaded", err));
+                        }
+                    }
+                }
+                KeyCode::Esc => self.seed_input = None,
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(menu) = &mut self.word_menu {
+            match key {
+                KeyCode::Up => menu.select_previous(),
+                KeyCode::Down => menu.select_next(),
+                KeyCode::Left | KeyCode::Right => {
+                    menu.cycle(&mut self.word_options, key == KeyCode::Right);
+                    self.settings.words = self.word_options;
+                    self.settings.save();
+                    if self.custom_passage.is_none() {
+                        self.reset_test();
+                    }
+                }
+                KeyCode::Esc | KeyCode::Enter => self.word_menu = None,
+                _ => {}
+            }
+            return;
+        }
+
+        if self.show_achievements {
+            if matches!(key, KeyCode::Esc | KeyCode::Enter)
+                || (key == KeyCode::Char('a') && modifiers.contains(KeyModifiers::CONTROL))
+            {
+                self.show_achievements = false;
+            }
+            return;
+        }
+
+        if self.show_personal_bests {
+            if matches!(key, KeyCode::Esc | KeyCode::Enter)
+                || (key == KeyCode::Char('p') && modifiers.contains(KeyModifiers::CONTROL))
+            {
+                self.show_personal_bests = false;
+            }
+            return;
+        }
+
+        if self.show_finger_stats {
+            if key == KeyCode::Enter
+                && let Some(finger) = FingerReport::new(&self.progress.results).weakest_finger()
+            {
+                self.show_finger_stats = false;
+                self.change_mode(Mode::FingerDrill(finger));
+            } else if key == KeyCode::Esc
+                || (key == KeyCode::Char('f') && modifiers.contains(KeyModifiers::CONTROL))
+            {
+                self.show_finger_stats = false;
+            }
+            return;
+        }
+
         match key {
             KeyCode::Tab => {
                 self.change_mode(self.mode.next());
@@ -123,77 +462,196 @@ impl App {
                 if self.end_time.is_some() {
                     // Test already complete - start a new one
                     self.reset_test();
-                } else if !self.typed_text.is_empty() {
+                } else if !self.session.is_empty() && self.session.can_finish_early() {
                     // Mid-test - finish early
                     self.finish_test();
                 }
             }
-            KeyCode::Backspace => {
-                if self.end_time.is_none() && !self.typed_text.is_empty() {
-                    self.typed_text.pop();
-                    self.calculate_stats();
-                }
+            KeyCode::Backspace
+                if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+                    && self.end_time.is_none() =>
+            {
+                self.session.delete_word();
+                self.calculate_stats();
+            }
+            KeyCode::Char('w' | 'h') if modifiers.contains(KeyModifiers::CONTROL) && self.end_time.is_none() => {
+                // Ctrl+W, and Ctrl+Backspace on terminals that send it as Ctrl+H
+                self.session.delete_word();
+                self.calculate_stats();
             }
-            KeyCode::Char('r') if modifiers.contains(KeyModifiers::CONTROL) && self.typed_text.is_empty() => {
+            KeyCode::Backspace if self.end_time.is_none() && !self.session.is_empty() => {
+                self.session.backspace();
+                self.calculate_stats();
+            }
+            KeyCode::Char('r') if modifiers.contains(KeyModifiers::CONTROL) && self.session.is_empty() => {
                 // Ctrl+R to reset history (only when not actively typing)
                 self.show_reset_confirmation = true;
             }
+            KeyCode::Char('e')
+                if modifiers.contains(KeyModifiers::CONTROL)
+                    && (self.session.is_empty() || self.end_time.is_some()) =>
+            {
+                // Ctrl+E to open the export menu (only when not actively typing)
+                self.export_menu = Some(ExportMenu::new());
+            }
+            KeyCode::Char('p')
+                if modifiers.contains(KeyModifiers::CONTROL)
+                    && (self.session.is_empty() || self.end_time.is_some()) =>
+            {
+                // Ctrl+P to show personal bests (only when not actively typing)
+                self.show_personal_bests = true;
+            }
+            KeyCode::Char('f')
+                if modifiers.contains(KeyModifiers::CONTROL)
+                    && (self.session.is_empty() || self.end_time.is_some()) =>
+            {
+                // Ctrl+F for per-finger stats (only when not actively typing)
+                self.show_finger_stats = true;
+            }
+            KeyCode::Char('a')
+                if modifiers.contains(KeyModifiers::CONTROL)
+                    && (self.session.is_empty() || self.end_time.is_some()) =>
+            {
+                // Ctrl+A to browse achievements (only when not actively typing)
+                self.show_achievements = true;
+            }
+            KeyCode::Char('n')
+                if modifiers.contains(KeyModifiers::CONTROL)
+                    && (self.session.is_empty() || self.end_time.is_some()) =>
+            {
+                // Ctrl+N to pick a word list language (only when not actively typing)
+                self.language_menu = Some(LanguageMenu::new(&self.language.name));
+            }
+            KeyCode::Char('d')
+                if modifiers.contains(KeyModifiers::CONTROL)
+                    && (self.session.is_empty() || self.end_time.is_some()) =>
+            {
+                // Ctrl+D for word options (only when not actively typing)
+                self.word_menu = Some(WordMenu::new());
+            }
+            KeyCode::Char('k')
+                if modifiers.contains(KeyModifiers::CONTROL)
+                    && (self.session.is_empty() || self.end_time.is_some()) =>
+            {
+                // Ctrl+K to enter a passage seed or code (only when not actively typing)
+                self.seed_input = Some(String::new());
+            }
+            KeyCode::Char('g') if modifiers.contains(KeyModifiers::CONTROL) => {
+                // Ctrl+G to cycle daily goal presets
+                self.settings.daily_goal = self.settings.daily_goal.next_preset();
+                self.settings.save();
+                self.update_streak();
+            }
+            KeyCode::Char('s')
+                if modifiers.contains(KeyModifiers::CONTROL)
+                    && (self.session.is_empty() || self.end_time.is_some()) =>
+            {
+                // Ctrl+S to cycle input modes (only when not actively typing)
+                self.settings.input_mode = self.settings.input_mode.next();
+                self.settings.save();
+                if self.end_time.is_none() {
+                    self.session.set_input_mode(self.settings.input_mode);
+                }
+            }
             KeyCode::Char('t') if modifiers.contains(KeyModifiers::CONTROL) => {
                 // Ctrl+T to cycle themes
                 self.theme = self.theme.next();
                 self.theme.save();
             }
-            KeyCode::Char('l') if modifiers.contains(KeyModifiers::CONTROL) => {
-                // Ctrl+L to cycle passage length
-                self.passage_length = self.passage_length.next();
-                self.reset_test();
+            KeyCode::Char('o') if modifiers.contains(KeyModifiers::CONTROL) => {
+                // Ctrl+O to cycle keyboard heatmaps
+                self.settings.heatmap = self.settings.heatmap.next();
+                self.settings.save();
             }
-            KeyCode::Char('1') if self.end_time.is_none() || self.typed_text.is_empty() => {
-                self.change_mode(Mode::FingerDrill(Finger::LeftPinky));
+            KeyCode::Char('b') if modifiers.contains(KeyModifiers::CONTROL) => {
+                // Ctrl+B to tint the keyboard by finger
+                self.settings.finger_overlay = !self.settings.finger_overlay;
+                self.settings.save();
             }
-            KeyCode::Char('2') if self.end_time.is_none() || self.typed_text.is_empty() => {
-                self.change_mode(Mode::FingerDrill(Finger::LeftRing));
+            KeyCode::Char('y') if modifiers.contains(KeyModifiers::CONTROL) => {
+                // Ctrl+Y to cycle the keyboard shape
+                self.settings.keyboard_layout = self.settings.keyboard_layout.next();
+                self.settings.save();
             }
-            KeyCode::Char('3') if self.end_time.is_none() || self.typed_text.is_empty() => {
-                self.change_mode(Mode::FingerDrill(Finger::LeftMiddle));
+            KeyCode::Char('x') if modifiers.contains(KeyModifiers::CONTROL) => {
+                // Ctrl+X to change how mistakes are marked
+                self.settings.error_cue = self.settings.error_cue.next();
+                self.settings.save();
+                self.toasts.retain(|toast| toast.title != "Error marking");
+                self.toasts.push(Toast::new("Error marking", self.settings.error_cue.name()));
             }
-            KeyCode::Char('4') if self.end_time.is_none() || self.typed_text.is_empty() => {
-                self.change_mode(Mode::FingerDrill(Finger::LeftIndex));
+            KeyCode::Char('v') if modifiers.contains(KeyModifiers::CONTROL) => {
+                // Ctrl+V to change the caret
+                self.settings.caret = self.settings.caret.next();
+                self.settings.save();
+                self.show_caret_toast();
             }
-            KeyCode::Char('6') if self.end_time.is_none() || self.typed_text.is_empty() => {
-                self.change_mode(Mode::FingerDrill(Finger::RightIndex));
+            KeyCode::Char('j') if modifiers.contains(KeyModifiers::CONTROL) => {
+                // Ctrl+J to make the caret glide to each new position or jump there
+                self.settings.smooth_caret = !self.settings.smooth_caret;
+                self.settings.save();
+                self.show_caret_toast();
             }
-            KeyCode::Char('7') if self.end_time.is_none() || self.typed_text.is_empty() => {
-                self.change_mode(Mode::FingerDrill(Finger::RightMiddle));
+            KeyCode::Char('z') if modifiers.contains(KeyModifiers::CONTROL) => {
+                // Ctrl+Z to switch between the full, compact and zen layouts
+                self.layout = self.layout.next();
+                self.settings.layout = self.layout;
+                self.settings.save();
+                self.toasts.retain(|toast| toast.title != "Layout");
+                self.toasts.push(Toast::new("Layout", self.layout.name()));
             }
-            KeyCode::Char('8') if self.end_time.is_none() || self.typed_text.is_empty() => {
-                self.change_mode(Mode::FingerDrill(Finger::RightRing));
+            KeyCode::Char(c @ ('k' | 's' | 'h' | 'c')) if modifiers.contains(KeyModifiers::ALT) => {
+                // Alt+K, S, H and C to show or hide the keyboard, sparkline,
+                // history and controls
+                let panels = &mut self.settings.panels;
+                let (shown, name) = match c {
+                    'k' => (&mut panels.keyboard, "Keyboard"),
+                    's' => (&mut panels.sparkline, "WPM history"),
+                    'h' => (&mut panels.history, "History"),
+                    _ => (&mut panels.controls, "Controls"),
+                };
+                *shown = !*shown;
+                let message = if *shown { "Shown" } else { "Hidden" };
+                self.settings.save();
+                self.toasts.retain(|toast| toast.title != name);
+                self.toasts.push(Toast::new(name, message));
             }
-            KeyCode::Char('9') if self.end_time.is_none() || self.typed_text.is_empty() => {
-                self.change_mode(Mode::FingerDrill(Finger::RightPinky));
+            KeyCode::Char('u') if modifiers.contains(KeyModifiers::CONTROL) => {
+                // Ctrl+U to switch the heatmap between this test and all time
+                self.settings.heatmap_scope = self.settings.heatmap_scope.toggle();
+                self.settings.save();
             }
-            KeyCode::Char(c) => {
-                if self.end_time.is_none() {
-                    if self.start_time.is_none() {
-                        self.start_time = Some(Instant::now());
-                    }
-
-                    if self.typed_text.len() < self.target_text.len() {
-                        self.typed_text.push(c);
-
-                        let target_char = self.target_text.chars().nth(self.typed_text.len() - 1);
-                        if target_char != Some(c) {
-                            if let Some(tc) = target_char {
-                                *self.char_errors.entry(tc).or_insert(0) += 1;
-                            }
-                        }
-
-                        if self.typed_text.len() == self.target_text.len() {
-                            self.finish_test();
-                        }
+            KeyCode::Char('l') if modifiers.contains(KeyModifiers::CONTROL) => {
+                // Ctrl+L to cycle passage length
+                self.passage_length = self.passage_length.next();
+                self.reset_test();
+            }
+            KeyCode::Char(c)
+                if let Some(finger) =
+                    digit_drill(c, &self.session, self.end_time.is_some(), self.custom_passage.is_some()) =>
+            {
+                self.change_mode(Mode::FingerDrill(finger));
+            }
+            KeyCode::Char('r' | 'R') if self.end_time.is_some() => {
+                self.retry_test();
+            }
+            // Ctrl or Alt with a letter is a shortcut, never typing, even when
+            // the shortcut is unavailable mid-test. Both at once is AltGr on
+            // Windows, which types accented letters.
+            KeyCode::Char(c)
+                if self.end_time.is_none()
+                    && modifiers.contains(KeyModifiers::CONTROL) == modifiers.contains(KeyModifiers::ALT) =>
+            {
+                if self.start_time.is_none() {
+                    self.start_time = Some(Instant::now());
+                }
 
-                        self.calculate_stats();
+                if !self.session.at_end() {
+                    self.session.type_char(c);
+                    if self.session.is_finished() {
+                        self.finish_test();
                     }
+                    self.calculate_stats();
                 }
             }
             _ => {}
@@ -202,73 +660,236 @@ impl App {
 
     fn calculate_stats(&mut self) {
         if let Some(start) = self.start_time {
-            let elapsed = start.elapsed().as_secs_f64();
+            // Freeze the clock once the test is over
+            let end = self.end_time.unwrap_or_else(Instant::now);
+            let elapsed = end.duration_since(start).as_secs_f64();
             if elapsed > 0.0 {
-                // Calculate accuracy by comparing typed chars to target chars directly
-                let correct_count = self.typed_text
-                    .chars()
-                    .zip(self.target_text.chars())
-                    .filter(|(typed, target)| typed == target)
-                    .count();
-
                 // WPM based on correct characters only (net WPM)
-                let correct_chars = correct_count as f64;
+                let correct_chars = self.session.correct_count() as f64;
                 let words_typed = correct_chars / 5.0;
                 let minutes = elapsed / 60.0;
                 self.current_wpm = words_typed / minutes;
 
-                if !self.typed_text.is_empty() {
-                    self.current_accuracy = (correct_count as f64 / self.typed_text.len() as f64) * 100.0;
-                } else {
-                    self.current_accuracy = 100.0;
-                }
+                self.current_accuracy = self.session.accuracy();
+                self.word_accuracy = self.session.word_accuracy(self.end_time.is_some());
             }
         }
     }
 
     fn finish_test(&mut self) {
         self.end_time = Some(Instant::now());
+        self.calculate_stats();
 
-        if let Some(start) = self.start_time {
-            let duration = start.elapsed();
+        if let (Some(start), Some(end)) = (self.start_time, self.end_time) {
+            let duration = end.duration_since(start);
 
             let result = TestResult {
                 wpm: self.current_wpm,
                 accuracy: self.current_accuracy,
                 timestamp: Utc::now(),
                 duration_secs: duration.as_secs(),
-                char_errors: self.char_errors.clone(),
+                char_errors: self.session.char_errors().clone(),
+                key_stats: self.session.key_stats().clone(),
+                mode: self.custom_passage.is_none().then_some(self.mode),
+                length: self.custom_passage.is_none().then_some(self.passage_length),
+                input_mode: self.settings.input_mode,
+                word_accuracy: Some(self.word_accuracy),
+                seed: self.custom_passage.is_none().then_some(self.seed),
+                language: self.custom_passage.is_none().then(|| self.language.name.clone()),
+                word_options: self.custom_passage.is_none().then_some(self.word_options),
+                custom_passage: self.custom_passage.as_ref().map(|passage| passage.name.clone()),
+                retry_of: self.first_attempt,
+                source: None,
+                import_id: None,
             };
 
+            // Check for a new record before this result joins the history
+            if let Some(key) = RecordKey::of(&result)
+                && result.wpm > 0.0
+            {
+                let previous_wpm = self.progress.personal_best(&key).map(|best| best.wpm);
+                if previous_wpm.is_none_or(|prev| result.wpm > prev) {
+                    self.new_personal_best = Some(PersonalBest {
+                        wpm: result.wpm,
+                        accuracy: result.accuracy,
+                        timestamp: result.timestamp,
+                        previous_wpm,
+                    });
+                }
+            }
+
+            self.first_attempt.get_or_insert(result.timestamp);
             self.progress.results.push(result);
             let _ = self.progress.save();
+            self.update_streak();
+            self.check_achievements();
         }
     }
 
-    fn get_current_char(&self) -> Option<char> {
-        if self.typed_text.len() < self.target_text.len() {
-            self.target_text.chars().nth(self.typed_text.len())
-        } else {
-            None
+    /// Unlock newly earned achievements and queue a toast for each
+    fn check_achievements(&mut self) {
+        let unlocked = self.achievements.evaluate(&self.progress, &self.streaks, &self.settings.daily_goal);
+        if unlocked.is_empty() {
+            return;
+        }
+        self.achievements.save();
+        for achievement in unlocked {
+            self.toasts.push(Toast::new(
+                format!("🏆 {}", achievement.name()),
+                achievement.description(),
+            ));
         }
     }
+
+    /// Extend the streak if today's goal has been met
+    fn update_streak(&mut self) {
+        let today = Local::now().date_naive();
+        if self.settings.daily_goal.progress(&self.progress.results, today).met
+            && self.streaks.record_goal_met(today)
+        {
+            self.streaks.save();
+        }
+    }
+
+    /// Errors and keystrokes of the test in progress, for the live heatmap
+    fn live_result(&self) -> TestResult {
+        TestResult {
+            timestamp: Utc::now(),
+            char_errors: self.session.char_errors().clone(),
+            key_stats: self.session.key_stats().clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Rebuild the keyboard heatmap if the history, the test in progress or
+    /// the heatmap settings changed since it was last built
+    fn update_heatmap(&mut self) {
+        let stamp = HeatmapStamp {
+            mode: self.settings.heatmap,
+            scope: self.settings.heatmap_scope,
+            results: self.progress.results.len(),
+            finished: self.end_time.is_some(),
+            presses: self.session.key_stats().values().map(|stats| stats.presses).sum(),
+            errors: self.session.char_errors().values().sum(),
+        };
+        if self.heatmap.as_ref().is_some_and(|(built, _)| *built == stamp) {
+            return;
+        }
+
+        // Once a test is finished its result is the last one in the history
+        let live;
+        let (session, history) = match self.progress.results.split_last() {
+            Some((last, earlier)) if stamp.finished => (last, earlier),
+            _ => {
+                live = self.live_result();
+                (&live, self.progress.results.as_slice())
+            }
+        };
+        let heatmap = Heatmap::new(
+            stamp.mode,
+            stamp.scope,
+            session,
+            history,
+            self.settings.improvement_days,
+            Utc::now(),
+        );
+        self.heatmap = Some((stamp, heatmap));
+    }
+
+    fn get_current_char(&self) -> Option<char> {
+        self.session.current().and_then(|g| g.chars().next())
+    }
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
+    let command = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(msg) => {
+            eprintln!("error: {}\n\nRun 'ratatap --help' for usage.", msg);
+            std::process::exit(2);
+        }
+    };
+
+    let outcome = match command {
+        Command::Run(options) => return run_tui(options),
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            Ok(())
+        }
+        Command::Version => {
+            println!("ratatap {}", env!("CARGO_PKG_VERSION"));
+            Ok(())
+        }
+        Command::Stats => {
+            cli::print_stats(&ProgressData::load());
+            Ok(())
+        }
+        Command::Export(options) => cli::export_history(&ProgressData::load(), &options),
+        Command::Goal(goal) => {
+            cli::goal(&ProgressData::load(), goal);
+            Ok(())
+        }
+        Command::Import { input, source } => {
+            cli::import_history(&mut ProgressData::load(), &input, source)
+        }
+    };
+
+    if let Err(msg) = outcome {
+        eprintln!("error: {}", msg);
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+fn run_tui(options: RunOptions) -> Result<(), Box<dyn Error>> {
+    // Load the passage before touching the terminal so errors print normally
+    let custom_passage = match &options.file {
+        Some(path) => match CustomPassage::load(path) {
+            Ok(passage) => Some(passage),
+            Err(msg) => {
+                eprintln!("error: {}", msg);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+    let language_name = match &options.seed {
+        Some(SeedEntry::Code(code)) => Some(&code.language),
+        _ => options.language.as_ref(),
+    };
+    let language = match language_name {
+        Some(name) => match Language::load(name) {
+            Ok(language) => Some(language),
+            Err(msg) => {
+                eprintln!("error: {}", msg);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    let screen_reader = options.screen_reader;
+    let mut app = App::new(options, custom_passage, language);
+    if screen_reader || app.settings.screen_reader {
+        return Ok(plain::run(&mut app)?);
+    }
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    // Only shown for the bar caret
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture, SetCursorStyle::SteadyBar)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new();
     let res = run_app(&mut terminal, &mut app);
 
     disable_raw_mode()?;
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        SetCursorStyle::DefaultUserShape
     )?;
     terminal.show_cursor()?;
 
@@ -286,154 +907,142 @@ fn run_app<B: ratatui::backend::Backend>(
     loop {
         terminal.draw(|f| ui(f, app))?;
 
-        if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    app.handle_key(key.code, key.modifiers);
-                }
-            }
+        // Tick faster while the smooth caret is still on its way
+        let position = app.session.position();
+        let tick = if app.caret.is_moving(position) { caret::FRAME } else { Duration::from_millis(100) };
+        if event::poll(tick)?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            app.handle_key(key.code, key.modifiers);
         }
 
+        // Pick up edits to a theme file while ratatap is running
+        if let Err(err) = app.theme.reload() {
+            app.toasts.push(Toast::new("🎨 Theme not reloaded", err));
+        }
+
+        app.toasts.retain(|toast| !toast.is_expired());
+        app.caret.tick(app.session.position(), Instant::now());
+
         if app.should_quit {
             return Ok(());
         }
     }
 }
 
-fn ui(f: &mut Frame, app: &App) {
-    let terminal_height = f.area().height;
-
-    let (keyboard_height, stats_height) = if terminal_height < 25 {
-        (0, 3)  // Hide keyboard in very small terminals
-    } else {
-        (8, 5)  // Show keyboard with full stats - increased for better spacing
-    };
-
-    let mut constraints = vec![
-        Constraint::Length(9),     // Title with ASCII art logo
-        Constraint::Min(6),        // Text to type - increased for readability
-    ];
-
-    if keyboard_height > 0 {
-        constraints.push(Constraint::Length(keyboard_height));
-    }
-
-    constraints.push(Constraint::Length(stats_height)); // Stats
-    constraints.push(Constraint::Length(4));            // Bottom info - increased for better spacing
+fn ui(f: &mut Frame, app: &mut App) {
+    let controls = controls_line(&app.theme);
+    let screen = panels::plan(
+        f.area(),
+        app.layout,
+        app.settings.panels,
+        passage_height(app.settings.error_cue),
+        |width| controls_height(&controls, width),
+    );
 
-    let main_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(constraints)
-        .split(f.area());
+    let indicator = indicator(app);
+    if let Some(area) = screen.logo {
+        // ASCII art logo with gradient colors
+        // All lines are the same width to maintain slant alignment
+        let logo_lines = [
+            r"    ____        __        __         ____   /\_/\ ~~, ",
+            r"   / __ \____ _/ /_____ _/ /_____ __/ __ \ ( o.o )  / ",
+            r"  / /_/ / __ `/ __/ __ `/ __/ __ `/ /_/ /   > ^ <  /  ",
+            r" / _, _/ /_/ / /_/ /_/ / /_/ /_/ / ____/   /|   |\/   ",
+            r"/_/ |_|\__,_/\__/\__,_/\__/\__,_/_/       (_|   |_)   ",
+        ];
 
-    // ASCII art logo with gradient colors
-    // All lines are the same width to maintain slant alignment
-    let logo_lines = [
-        r"    ____        __        __         ____   /\_/\ ~~, ",
-        r"   / __ \____ _/ /_____ _/ /_____ __/ __ \ ( o.o )  / ",
-        r"  / /_/ / __ `/ __/ __ `/ __/ __ `/ /_/ /   > ^ <  /  ",
-        r" / _, _/ /_/ / /_/ /_/ / /_/ /_/ / ____/   /|   |\/   ",
-        r"/_/ |_|\__,_/\__/\__,_/\__/\__,_/_/       (_|   |_)   ",
-    ];
+        let mut title_lines: Vec<Line> = Vec::new();
+        let logo_width = logo_lines[0].len();
 
-    let mut title_lines: Vec<Line> = Vec::new();
-    let logo_width = logo_lines[0].len();
-
-    // Add each logo line with gradient
-    for (line_idx, logo_line) in logo_lines.iter().enumerate() {
-        let mut spans = Vec::new();
-        for (i, ch) in logo_line.chars().enumerate() {
-            // Gradient based on horizontal position and line
-            let h_ratio = i as f64 / logo_width.max(1) as f64;
-            let v_ratio = line_idx as f64 / logo_lines.len() as f64;
-            let ratio = (h_ratio + v_ratio) / 2.0;
-            let color = interpolate_color(app.theme.primary(), app.theme.secondary(), ratio);
-            spans.push(Span::styled(
-                ch.to_string(),
-                Style::default().fg(color).add_modifier(Modifier::BOLD),
-            ));
+        // Add each logo line with gradient
+        for (line_idx, logo_line) in logo_lines.iter().enumerate() {
+            let mut spans = Vec::new();
+            for (i, ch) in logo_line.chars().enumerate() {
+                // Gradient based on horizontal position and line
+                let h_ratio = i as f64 / logo_width.max(1) as f64;
+                let v_ratio = line_idx as f64 / logo_lines.len() as f64;
+                let ratio = (h_ratio + v_ratio) / 2.0;
+                let color = interpolate_color(app.theme.primary(), app.theme.secondary(), ratio);
+                spans.push(Span::styled(
+                    ch.to_string(),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ));
+            }
+            title_lines.push(Line::from(spans));
         }
-        title_lines.push(Line::from(spans));
-    }
 
-    // Add mode/theme/length indicator line (centered within logo width)
-    let indicator = format!("[{}] {} ({})", app.theme.name(), app.mode.name(), app.passage_length.name());
-    let padded_indicator = format!("{:^width$}", indicator, width = logo_width);
-    title_lines.push(Line::from(Span::styled(
-        padded_indicator,
-        subtitle_style(&app.theme),
-    )));
+        // Mode/theme/length indicator line (centered within logo width)
+        let padded_indicator = format!("{:^width$}", indicator, width = logo_width);
+        title_lines.push(Line::from(Span::styled(
+            padded_indicator,
+            subtitle_style(&app.theme),
+        )));
 
-    let title = Paragraph::new(title_lines)
-        .alignment(Alignment::Center)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(app.theme.primary()))
-        );
-    f.render_widget(title, main_chunks[0]);
+        let title = Paragraph::new(title_lines)
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(app.theme.primary()))
+            );
+        f.render_widget(title, area);
+    }
 
-    let mut text_spans = Vec::new();
-    let typed_chars: Vec<char> = app.typed_text.chars().collect();
-    let target_chars: Vec<char> = app.target_text.chars().collect();
+    // Strict input modes keep the test open until the passage is correct
+    let blocked = app.end_time.is_none() && app.session.at_end() && app.session.finish_blocked();
+    // Without the logo the passage title takes over its indicator line
+    let mut text_title = if screen.logo.is_some() { "Text to Type".to_string() } else { indicator };
+    if blocked {
+        text_title.push_str(" - fix errors to finish");
+    }
 
-    for (i, &target_char) in target_chars.iter().enumerate() {
-        let style = if i < typed_chars.len() {
-            if typed_chars[i] == target_char {
-                correct_char_style(&app.theme)
-            } else {
-                incorrect_char_style(&app.theme)
-            }
-        } else if i == typed_chars.len() {
-            current_char_style(&app.theme)
-        } else {
-            pending_char_style(&app.theme)
-        };
-        text_spans.push(Span::styled(target_char.to_string(), style));
+    let mut text_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(app.theme.secondary()))
+        .title(text_title)
+        .title_style(subtitle_style(&app.theme));
+    // Shown so a passage can be shared and typed again with Ctrl+K or --seed
+    if let Some(code) = app.passage_code() {
+        text_block = text_block.title(Line::from(format!("Seed {}", code)).right_aligned());
+    } else if app.custom_passage.is_none() {
+        text_block = text_block.title(Line::from(format!("Seed {} (not shareable)", app.seed)).right_aligned());
+    }
+    if app.layout == LayoutPreset::Zen {
+        text_block = Block::new();
     }
 
-    let text_widget = Paragraph::new(Line::from(text_spans))
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(app.theme.secondary()))
-                .title("Text to Type")
-                .title_style(subtitle_style(&app.theme)),
-        )
-        .wrap(Wrap { trim: false })
-        .alignment(Alignment::Left);
-    f.render_widget(text_widget, main_chunks[1]);
+    let markup = Markup {
+        cue: app.settings.error_cue,
+        caret: app.settings.caret,
+        caret_position: if app.settings.smooth_caret { app.caret.position() } else { app.session.position() },
+        theme: &app.theme,
+    };
+    let caret = render_passage(f, screen.text, text_block, &app.session, &app.passage_layout, &markup);
 
-    let mut chunk_idx = 2;
+    if let Some(area) = screen.live_wpm {
+        render_live_wpm(f, area, app, blocked);
+    }
 
-    if keyboard_height > 0 {
-        // Merge historical errors with current session errors for real-time feedback
-        let mut char_stats = app.progress.get_char_error_analysis();
-        for (&ch, &count) in &app.char_errors {
-            let stats = char_stats.entry(ch).or_insert(stats::CharStats {
-                total_errors: 0,
-                total_appearances: 0,
-            });
-            stats.total_errors += count;
+    if let Some(area) = screen.keyboard {
+        app.update_heatmap();
+        if let Some((_, heatmap)) = &app.heatmap {
+            render_keyboard(
+                f,
+                area,
+                app.get_current_char(),
+                heatmap,
+                app.settings.finger_overlay,
+                app.settings.keyboard_layout,
+                &app.theme,
+            );
         }
-        render_keyboard(
-            f,
-            main_chunks[chunk_idx],
-            app.get_current_char(),
-            &char_stats,
-            &app.theme,
-        );
-        chunk_idx += 1;
     }
 
-    let stats_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(main_chunks[chunk_idx]);
-
-    let total_errors: usize = app.char_errors.values().sum();
+    let total_errors: usize = app.session.char_errors().values().sum();
 
     // Build stats content with styled spans
     let acc_color = if app.current_accuracy >= 95.0 {
@@ -445,18 +1054,47 @@ fn ui(f: &mut Frame, app: &App) {
     };
 
     let stats_lines = if app.end_time.is_some() {
+        let attempts = app.first_attempt.map(|first| app.progress.attempts(first)).unwrap_or_default();
+        let (headline, record_line) = match &app.new_personal_best {
+            Some(pb) => (
+                Span::styled(
+                    " ★ NEW PERSONAL BEST! ★ ",
+                    Style::default().fg(app.theme.current_fg()).bg(app.theme.current_bg()).add_modifier(Modifier::BOLD),
+                ),
+                Line::from(Span::styled(
+                    match pb.previous_wpm {
+                        Some(prev) => format!("+{:.1} WPM over {:.1}", pb.wpm - prev, prev),
+                        None => format!("First record for {} ({})", app.mode.name(), app.passage_length.name()),
+                    },
+                    Style::default().fg(app.theme.warning()),
+                )),
+            ),
+            None => (
+                Span::styled("✓ Test Complete!", Style::default().fg(app.theme.correct()).add_modifier(Modifier::BOLD)),
+                Line::from(""),
+            ),
+        };
+        // On a retry, show how the passage has gone so far instead
+        let record_line = if attempts.len() > 1 {
+            attempts_line(&attempts, &app.theme)
+        } else {
+            record_line
+        };
         vec![
-            Line::from(Span::styled("✓ Test Complete!", Style::default().fg(app.theme.correct()).add_modifier(Modifier::BOLD))),
-            Line::from(""),
+            Line::from(headline),
+            record_line,
             Line::from(vec![
                 Span::styled("WPM: ", subtitle_style(&app.theme)),
                 Span::styled(format!("{:.1}", app.current_wpm), Style::default().fg(wpm_color(&app.theme, app.current_wpm)).add_modifier(Modifier::BOLD)),
                 Span::raw("  "),
                 Span::styled("Acc: ", subtitle_style(&app.theme)),
                 Span::styled(format!("{:.1}%", app.current_accuracy), Style::default().fg(app.theme.correct())),
+                Span::raw("  "),
+                Span::styled("Words: ", subtitle_style(&app.theme)),
+                Span::styled(format!("{:.0}%", app.word_accuracy), Style::default().fg(app.theme.correct())),
             ]),
             Line::from(""),
-            Line::from(Span::styled("Press Enter for new test", subtitle_style(&app.theme))),
+            Line::from(Span::styled("Press Enter for new text, R to retry this one", subtitle_style(&app.theme))),
         ]
     } else {
         vec![
@@ -470,8 +1108,11 @@ fn ui(f: &mut Frame, app: &App) {
             Line::from(vec![
                 Span::styled("Errors: ", subtitle_style(&app.theme)),
                 Span::styled(format!("{}", total_errors), Style::default().fg(if total_errors == 0 { app.theme.correct() } else { app.theme.error() })),
+                Span::raw("  "),
+                Span::styled("Words: ", subtitle_style(&app.theme)),
+                Span::styled(format!("{:.0}%", app.word_accuracy), subtitle_style(&app.theme)),
             ]),
-            render_inline_progress(app.typed_text.len(), app.target_text.len(), &app.theme),
+            render_inline_progress(app.session.position(), app.session.target().len(), &app.theme),
         ]
     };
 
@@ -485,17 +1126,14 @@ fn ui(f: &mut Frame, app: &App) {
                 .title_style(subtitle_style(&app.theme)),
         )
         .alignment(Alignment::Left);
-    f.render_widget(stats_widget, stats_chunks[0]);
-
-    let wpm_history = app.progress.get_wpm_history(20);
-    render_wpm_sparkline(f, stats_chunks[1], &wpm_history, &app.theme);
-
-    chunk_idx += 1;
+    if let Some(area) = screen.stats {
+        f.render_widget(stats_widget, area);
+    }
 
-    let bottom_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(main_chunks[chunk_idx]);
+    if let Some(area) = screen.sparkline {
+        let wpm_history = app.progress.get_wpm_history(20);
+        render_wpm_sparkline(f, area, &wpm_history, &app.theme);
+    }
 
     // History panel with styled content
     let history_lines = vec![
@@ -509,6 +1147,7 @@ fn ui(f: &mut Frame, app: &App) {
             Span::styled("Avg Acc: ", subtitle_style(&app.theme)),
             Span::styled(format!("{:.1}%", app.progress.average_accuracy()), Style::default().fg(app.theme.correct())),
         ]),
+        goal_line(app),
     ];
 
     let history_widget = Paragraph::new(history_lines)
@@ -521,37 +1160,42 @@ fn ui(f: &mut Frame, app: &App) {
                 .title_style(subtitle_style(&app.theme)),
         )
         .alignment(Alignment::Center);
-    f.render_widget(history_widget, bottom_chunks[0]);
-
-    // Controls panel with styled shortcuts
-    let controls = Line::from(vec![
-        Span::styled("Tab", Style::default().fg(app.theme.primary()).add_modifier(Modifier::BOLD)),
-        Span::styled(":Mode ", subtitle_style(&app.theme)),
-        Span::styled("1-4,6-9", Style::default().fg(app.theme.primary()).add_modifier(Modifier::BOLD)),
-        Span::styled(":Finger ", subtitle_style(&app.theme)),
-        Span::styled("^T", Style::default().fg(app.theme.warning()).add_modifier(Modifier::BOLD)),
-        Span::styled(":Theme ", subtitle_style(&app.theme)),
-        Span::styled("^L", Style::default().fg(app.theme.warning()).add_modifier(Modifier::BOLD)),
-        Span::styled(":Length ", subtitle_style(&app.theme)),
-        Span::styled("Enter", Style::default().fg(app.theme.correct()).add_modifier(Modifier::BOLD)),
-        Span::styled(":Retry ", subtitle_style(&app.theme)),
-        Span::styled("^R", Style::default().fg(app.theme.error()).add_modifier(Modifier::BOLD)),
-        Span::styled(":Reset ", subtitle_style(&app.theme)),
-        Span::styled("Esc", Style::default().fg(app.theme.secondary()).add_modifier(Modifier::BOLD)),
-        Span::styled(":Quit", subtitle_style(&app.theme)),
-    ]);
-
-    let controls_widget = Paragraph::new(controls)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(app.theme.secondary()))
-                .title("Controls")
-                .title_style(subtitle_style(&app.theme)),
-        )
-        .alignment(Alignment::Center);
-    f.render_widget(controls_widget, bottom_chunks[1]);
+    if let Some(area) = screen.history {
+        f.render_widget(history_widget, area);
+    }
+
+    if let Some(area) = screen.controls {
+        f.render_widget(controls_panel(controls, &app.theme), area);
+    }
+
+    if app.show_achievements {
+        render_achievements(f, &app.achievements, &app.theme);
+    }
+
+    if app.show_personal_bests {
+        render_personal_bests(f, &app.progress, &app.record_key(), &app.theme);
+    }
+
+    if app.show_finger_stats {
+        render_finger_stats(f, &FingerReport::new(&app.progress.results), &app.theme);
+    }
+
+    if let Some(menu) = &app.export_menu {
+        render_export_menu(f, menu, &app.theme);
+    }
+
+    if let Some(input) = &app.seed_input {
+        let current = app.passage_code().map_or_else(|| app.seed.to_string(), |code| code.to_string());
+        ui::dialogs::render_seed_dialog(f, input, &current, &app.theme);
+    }
+
+    if let Some(menu) = &app.word_menu {
+        render_word_menu(f, menu, &app.word_options, &app.language, &app.theme);
+    }
+
+    if let Some(menu) = &app.language_menu {
+        render_language_menu(f, menu, &app.language.name, &app.theme);
+    }
 
     // Render confirmation dialog on top if active
     if app.show_reset_confirmation {
@@ -562,4 +1206,270 @@ fn ui(f: &mut Frame, app: &App) {
             &app.theme,
         );
     }
+
+    render_toasts(f, &mut app.toasts, &app.theme);
+
+    // The bar caret is the terminal cursor, which would show through panels
+    if app.settings.caret == CaretStyle::Bar
+        && !app.has_overlay()
+        && let Some(caret) = caret
+    {
+        f.set_cursor_position(caret);
+    }
+
+    app.color_depth.apply(f.buffer_mut(), &app.theme);
+}
+
+/// Finger drill a digit key switches to. Digits are shortcuts between
+/// tests and before a generated passage is started; mid-test, or whenever a
+/// file's passage could still be typed, they are typing.
+fn digit_drill(key: char, session: &TypingSession, finished: bool, custom_passage: bool) -> Option<Finger> {
+    if !finished && (custom_passage || !session.is_empty()) {
+        return None;
+    }
+    let finger = match key {
+        '1' => Finger::LeftPinky,
+        '2' => Finger::LeftRing,
+        '3' => Finger::LeftMiddle,
+        '4' => Finger::LeftIndex,
+        '6' => Finger::RightIndex,
+        '7' => Finger::RightMiddle,
+        '8' => Finger::RightRing,
+        '9' => Finger::RightPinky,
+        _ => return None,
+    };
+    Some(finger)
+}
+
+/// Every shortcut, styled, as one line for the controls panel to wrap
+fn controls_line(theme: &Theme) -> Line<'static> {
+    Line::from(vec![
+        Span::styled("Tab", Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Mode ", subtitle_style(theme)),
+        Span::styled("1-4,6-9", Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Finger ", subtitle_style(theme)),
+        Span::styled("^T", Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Theme ", subtitle_style(theme)),
+        Span::styled("^O/^U", Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Heatmap ", subtitle_style(theme)),
+        Span::styled("^B", Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Guide ", subtitle_style(theme)),
+        Span::styled("^Y", Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Board ", subtitle_style(theme)),
+        Span::styled("^X", Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Errors ", subtitle_style(theme)),
+        Span::styled("^V/^J", Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Caret ", subtitle_style(theme)),
+        Span::styled("^Z", Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Layout ", subtitle_style(theme)),
+        Span::styled("Alt+K/S/H/C", Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Panels ", subtitle_style(theme)),
+        Span::styled("^L", Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Length ", subtitle_style(theme)),
+        Span::styled("^E", Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Export ", subtitle_style(theme)),
+        Span::styled("^P", Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Bests ", subtitle_style(theme)),
+        Span::styled("^F", Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Fingers ", subtitle_style(theme)),
+        Span::styled("^G", Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Goal ", subtitle_style(theme)),
+        Span::styled("^A", Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Awards ", subtitle_style(theme)),
+        Span::styled("^S", Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Input ", subtitle_style(theme)),
+        Span::styled("^N", Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Language ", subtitle_style(theme)),
+        Span::styled("^D", Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Words ", subtitle_style(theme)),
+        Span::styled("^K", Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Seed ", subtitle_style(theme)),
+        Span::styled("Enter", Style::default().fg(theme.correct()).add_modifier(Modifier::BOLD)),
+        Span::styled(":New ", subtitle_style(theme)),
+        Span::styled("R", Style::default().fg(theme.correct()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Retry ", subtitle_style(theme)),
+        Span::styled("^R", Style::default().fg(theme.error()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Reset ", subtitle_style(theme)),
+        Span::styled("Esc", Style::default().fg(theme.secondary()).add_modifier(Modifier::BOLD)),
+        Span::styled(":Quit", subtitle_style(theme)),
+    ])
+}
+
+/// Rows the controls panel needs at `width`, border included. Wraps the way
+/// the panel's paragraph does: between words, with a word too long for a
+/// row broken wherever it runs out of room.
+fn controls_height(controls: &Line, width: u16) -> u16 {
+    let width = width.saturating_sub(2).max(1) as usize;
+    let text: String = controls.spans.iter().map(|span| span.content.as_ref()).collect();
+    let mut rows = 1;
+    let mut used = 0;
+    for word in text.split_whitespace() {
+        let mut word = word.chars().count();
+        if used > 0 && used + 1 + word <= width {
+            used += 1 + word;
+            continue;
+        }
+        if used > 0 {
+            rows += 1;
+        }
+        while word > width {
+            word -= width;
+            rows += 1;
+        }
+        used = word;
+    }
+    rows + 2
+}
+
+/// The controls panel around the shortcuts line
+fn controls_panel(controls: Line<'static>, theme: &Theme) -> Paragraph<'static> {
+    Paragraph::new(controls)
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.secondary()))
+                .title("Controls")
+                .title_style(subtitle_style(theme)),
+        )
+        .alignment(Alignment::Center)
+}
+
+/// Zen mode's one line under the passage: live WPM while typing, the
+/// results once the test is over
+fn render_live_wpm(f: &mut Frame, area: Rect, app: &App, blocked: bool) {
+    let wpm = Span::styled(
+        format!("{:.0} wpm", app.current_wpm),
+        Style::default().fg(wpm_color(&app.theme, app.current_wpm)).add_modifier(Modifier::BOLD),
+    );
+    let line = if app.end_time.is_some() {
+        Line::from(vec![
+            wpm,
+            Span::styled(
+                format!("  {:.0}% acc  ·  Enter: new  R: retry", app.current_accuracy),
+                subtitle_style(&app.theme),
+            ),
+        ])
+    } else if blocked {
+        Line::from(vec![wpm, Span::styled("  ·  fix errors to finish", Style::default().fg(app.theme.error()))])
+    } else if app.session.is_empty() {
+        Line::from(Span::styled("zen · ^Z: layout", subtitle_style(&app.theme)))
+    } else {
+        Line::from(wpm)
+    };
+    f.render_widget(Paragraph::new(line).alignment(Alignment::Center), area);
+}
+
+/// Theme, mode, length and other options in effect, e.g.
+/// `[Nord] Normal (Medium) · German`
+fn indicator(app: &App) -> String {
+    let mut indicator = match &app.custom_passage {
+        Some(passage) => format!("[{}] File: {}", app.theme.name(), passage.name),
+        None => format!("[{}] {} ({})", app.theme.name(), app.mode.name(), app.passage_length.name()),
+    };
+    // Finger drills are spelled after the word list too, but ignore the tier
+    if app.custom_passage.is_none() {
+        if app.language.name != language::DEFAULT_LANGUAGE {
+            indicator.push_str(&format!(" · {}", app.language.display_name()));
+        }
+        let difficulty = app.word_options.difficulty;
+        if app.mode.uses_words() && difficulty != Difficulty::default() {
+            indicator.push_str(&format!(" · {}", difficulty.name()));
+            if !difficulty.available(&app.language) {
+                indicator.push_str(" (whole list)");
+            }
+        }
+    }
+    if app.settings.input_mode != InputMode::Normal {
+        indicator.push_str(&format!(" · {}", app.settings.input_mode.name()));
+    }
+    indicator
+}
+
+/// Today's progress toward the daily goal plus the current and longest streak
+fn goal_line(app: &App) -> Line<'static> {
+    let today = Local::now().date_naive();
+    let goal = app.settings.daily_goal;
+    let progress = goal.progress(&app.progress.results, today);
+
+    let amount = match goal {
+        goals::DailyGoal::Minutes { minutes } => format!("{:.0}/{} min", progress.current.floor(), minutes),
+        goals::DailyGoal::Tests { count } => format!("{:.0}/{} tests", progress.current, count),
+        goals::DailyGoal::Wpm { wpm, accuracy } => {
+            format!("{:.0}/{:.0} WPM @ {:.0}%", progress.current, wpm, accuracy)
+        }
+    };
+    let filled = (progress.ratio() * 10.0).round() as usize;
+    let goal_color = if progress.met { app.theme.correct() } else { app.theme.warning() };
+    let streak = app.streaks.current_as_of(today);
+
+    Line::from(vec![
+        Span::styled("Goal: ", subtitle_style(&app.theme)),
+        Span::styled(amount, Style::default().fg(goal_color).add_modifier(Modifier::BOLD)),
+        Span::styled(
+            format!(" {}{}", "▰".repeat(filled), "▱".repeat(10 - filled)),
+            Style::default().fg(goal_color),
+        ),
+        Span::raw("  "),
+        Span::styled("Streak: ", subtitle_style(&app.theme)),
+        Span::styled(
+            format!("{}d", streak),
+            Style::default().fg(app.theme.primary()).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(format!(" (best {}d)", app.streaks.longest), subtitle_style(&app.theme)),
+    ])
+}
+
+/// WPM of each attempt at one passage, e.g. `Attempt 3: 48 → 52 → 57 WPM (+9.0)`
+fn attempts_line(attempts: &[&TestResult], theme: &Theme) -> Line<'static> {
+    const SHOWN: usize = 5;
+    let first = attempts[0].wpm;
+    let latest = attempts[attempts.len() - 1].wpm;
+
+    let mut spans = vec![Span::styled(format!("Attempt {}: ", attempts.len()), subtitle_style(theme))];
+    if attempts.len() > SHOWN {
+        spans.push(Span::styled("… → ", subtitle_style(theme)));
+    }
+    let recent = &attempts[attempts.len().saturating_sub(SHOWN)..];
+    for (i, attempt) in recent.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::styled(" → ", subtitle_style(theme)));
+        }
+        spans.push(Span::styled(format!("{:.0}", attempt.wpm), Style::default().fg(wpm_color(theme, attempt.wpm))));
+    }
+    let change = latest - first;
+    let change_color = if change >= 0.0 { theme.correct() } else { theme.error() };
+    spans.push(Span::styled(" WPM ", subtitle_style(theme)));
+    spans.push(Span::styled(format!("({:+.1})", change), Style::default().fg(change_color).add_modifier(Modifier::BOLD)));
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digits_are_typed_once_a_passage_can_take_them() {
+        let input_mode = InputMode::Normal;
+        let generated = TypingSession::new("the cat", input_mode);
+        assert_eq!(digit_drill('1', &generated, false, false), Some(Finger::LeftPinky));
+        assert_eq!(digit_drill('5', &generated, false, false), None);
+
+        // A file's passage may contain digits from the first key on
+        let mut file = TypingSession::new("room 101", input_mode);
+        assert_eq!(digit_drill('9', &file, false, true), None);
+        for c in "room 1".chars() {
+            file.type_char(c);
+        }
+        assert_eq!(digit_drill('0', &file, false, true), None);
+        assert_eq!(digit_drill('1', &file, false, true), None);
+
+        let mut started = TypingSession::new("the cat", input_mode);
+        started.type_char('t');
+        assert_eq!(digit_drill('2', &started, false, false), None);
+        // Once the test is over they pick a drill again
+        assert_eq!(digit_drill('2', &started, true, false), Some(Finger::LeftRing));
+        assert_eq!(digit_drill('2', &file, true, true), Some(Finger::LeftRing));
+    }
 }
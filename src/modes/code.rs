@@ -0,0 +1,118 @@
+//! Passage codes: everything that decides a generated passage, in one string
+//! that can be shared and typed back in.
+//!
+//! A code reads `mode/length/language/word options/seed`, for example
+//! `normal/medium/english/top1k,min3/482913`. Weak-letter passages
+//! depend on the player's own error history, so they have no code.
+
+use std::fmt;
+
+use super::words::WordOptions;
+use super::{Mode, PassageLength};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PassageCode {
+    pub mode: Mode,
+    pub length: PassageLength,
+    pub language: String,
+    pub words: WordOptions,
+    pub seed: u64,
+}
+
+/// What was entered as a seed
+#[derive(Debug, Clone, PartialEq)]
+pub enum SeedEntry {
+    /// A bare seed, generated with the current mode and options
+    Seed(u64),
+    Code(Box<PassageCode>),
+}
+
+impl PassageCode {
+    /// The code for a passage, or `None` when it can't be shared
+    pub fn new(mode: Mode, length: PassageLength, language: &str, words: WordOptions, seed: u64) -> Option<Self> {
+        (mode != Mode::WeakLetter).then(|| PassageCode { mode, length, language: language.to_string(), words, seed })
+    }
+}
+
+impl fmt::Display for PassageCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}/{}/{}/{}/{}",
+            self.mode.slug(),
+            self.length.name().to_lowercase(),
+            self.language,
+            self.words.tags(),
+            self.seed
+        )
+    }
+}
+
+impl SeedEntry {
+    pub fn parse(input: &str) -> Result<SeedEntry, String> {
+        let input = input.trim();
+        if let Ok(seed) = input.parse::<u64>() {
+            return Ok(SeedEntry::Seed(seed));
+        }
+
+        let parts: Vec<&str> = input.split('/').collect();
+        let [mode, length, language, words, seed] = parts[..] else {
+            return Err(format!(
+                "invalid seed '{}' (expected a whole number or a code like normal/medium/english/top200/1234)",
+                input
+            ));
+        };
+        let mode = Mode::from_name(mode).ok_or_else(|| format!("unknown mode '{}'", mode))?;
+        if mode == Mode::WeakLetter {
+            return Err("weak-letter passages depend on your own history and can't be shared".to_string());
+        }
+        let length = PassageLength::from_name(length).ok_or_else(|| format!("unknown length '{}'", length))?;
+        if language.is_empty() {
+            return Err("the code is missing a word list".to_string());
+        }
+        Ok(SeedEntry::Code(Box::new(PassageCode {
+            mode,
+            length,
+            language: language.to_lowercase(),
+            words: WordOptions::from_tags(words)?,
+            seed: seed.parse().map_err(|_| format!("invalid seed '{}' (expected a whole number)", seed))?,
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finger_map::Finger;
+    use crate::modes::words::Difficulty;
+
+    #[test]
+    fn codes_read_back_as_written() {
+        let words = WordOptions {
+            difficulty: Difficulty::Top1k,
+            min_length: Some(3),
+            max_length: Some(9),
+            pseudo_words: true,
+            avoid_repeats: false,
+        };
+        let code = PassageCode::new(Mode::FingerDrill(Finger::LeftIndex), PassageLength::Long, "german", words, 42).unwrap();
+        assert_eq!(code.to_string(), "left-index/long/german/top1k,min3,max9,madeup,repeats/42");
+        assert_eq!(SeedEntry::parse(&code.to_string()), Ok(SeedEntry::Code(Box::new(code))));
+
+        let code = PassageCode::new(Mode::Normal, PassageLength::Medium, "english", WordOptions::default(), 7).unwrap();
+        assert_eq!(code.to_string(), "normal/medium/english/top200/7");
+    }
+
+    #[test]
+    fn a_bare_number_is_a_seed() {
+        assert_eq!(SeedEntry::parse(" 1234 "), Ok(SeedEntry::Seed(1234)));
+        assert!(SeedEntry::parse("12a").unwrap_err().starts_with("invalid seed '12a'"));
+        assert_eq!(SeedEntry::parse("normal/medium/english/top200,loud/1").unwrap_err(), "unknown word option 'loud'");
+    }
+
+    #[test]
+    fn weak_letter_passages_have_no_code() {
+        assert_eq!(PassageCode::new(Mode::WeakLetter, PassageLength::Short, "english", WordOptions::default(), 1), None);
+        assert!(SeedEntry::parse("weak/short/english/top200/1").is_err());
+    }
+}
@@ -0,0 +1,30 @@
+use std::fs;
+use std::path::Path;
+
+/// A user-supplied passage loaded from a text file
+#[derive(Debug, Clone)]
+pub struct CustomPassage {
+    pub name: String,
+    pub text: String,
+}
+
+impl CustomPassage {
+    /// Read a passage from disk, collapsing line breaks and runs of
+    /// whitespace into single spaces so it can be typed as one line.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let data = fs::read_to_string(path)
+            .map_err(|e| format!("could not read '{}': {}", path.display(), e))?;
+
+        let text = data.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.is_empty() {
+            return Err(format!("'{}' does not contain any text to practice", path.display()));
+        }
+
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+
+        Ok(CustomPassage { name, text })
+    }
+}
@@ -1,16 +1,42 @@
 use rand::seq::SliceRandom;
+use rand::Rng;
 
-use crate::finger_map::{get_keys_for_finger, Finger};
+use super::markov::Markov;
+use super::words::WordOptions;
+use crate::finger_map::{finger_for_key, get_keys_for_finger, row_for_key, Finger, Row};
 
-pub fn generate_text(finger: Finger, word_count: usize) -> String {
+/// Build a drill of made-up words spelled like the language's own words,
+/// using the finger's letters plus the home-row letters of the same hand,
+/// with the finger's keys making up at least half of every word.
+///
+/// Some fingers have too few letters for that: the right pinky's only letter
+/// is `p`, and no word is half p's. Those drill real words from the list that
+/// contain the finger's keys instead (e.g. "people", "don't"). Plain key
+/// patterns are the last resort, when the list has no such words (e.g. a
+/// non-Latin script).
+pub fn generate_text(
+    finger: Finger,
+    words: &[String],
+    options: &WordOptions,
+    word_count: usize,
+    rng: &mut impl Rng,
+) -> String {
     let keys = get_keys_for_finger(finger);
-    let mut rng = rand::thread_rng();
+    let letters: Vec<char> = keys.iter().copied().filter(|c| c.is_alphabetic()).collect();
+    let anchors = home_row_anchors(finger);
 
-    let patterns = generate_patterns(&keys);
+    if let Some(drill) = pseudo_words(&letters, &anchors, words, options, word_count, rng) {
+        return drill;
+    }
+    if let Some(drill) = real_words(&keys, words, options, word_count, rng) {
+        return drill;
+    }
+
+    let patterns = generate_patterns(&letters, &anchors);
 
     let mut result = Vec::new();
     for _ in 0..word_count {
-        if let Some(pattern) = patterns.choose(&mut rng) {
+        if let Some(pattern) = patterns.choose(rng) {
             result.push(pattern.clone());
         }
     }
@@ -18,32 +44,99 @@ pub fn generate_text(finger: Finger, word_count: usize) -> String {
     result.join(" ")
 }
 
-fn generate_patterns(keys: &[char]) -> Vec<String> {
-    let mut patterns = Vec::new();
+/// Home-row letters typed by the other fingers of the same hand
+fn home_row_anchors(finger: Finger) -> Vec<char> {
+    ('a'..='z')
+        .filter(|&c| row_for_key(c) == Some(Row::Home))
+        .filter(|&c| finger_for_key(c).is_some_and(|f| f != finger && f.hand() == finger.hand()))
+        .collect()
+}
 
-    for i in 0..keys.len() {
-        for j in 0..keys.len() {
-            if keys[i].is_alphabetic() && keys[j].is_alphabetic() {
-                patterns.push(format!("{}{}", keys[i], keys[j]).repeat(2));
-            }
+fn pseudo_words(
+    letters: &[char],
+    anchors: &[char],
+    words: &[String],
+    options: &WordOptions,
+    word_count: usize,
+    rng: &mut impl Rng,
+) -> Option<String> {
+    let markov = Markov::train(words);
+    let min = options.min_length.unwrap_or(3);
+    let max = options.max_length.unwrap_or(7).max(min);
+    let mut drill: Vec<String> = Vec::with_capacity(word_count);
+
+    while drill.len() < word_count {
+        let word = markov.generate(
+            |c| letters.contains(&c) || anchors.contains(&c),
+            |w| drills_finger(w, letters) && !(options.avoid_repeats && drill.last().is_some_and(|last| last == w)),
+            min,
+            max,
+            rng,
+        )?;
+        drill.push(word);
+    }
+
+    Some(drill.join(" "))
+}
+
+/// Words from the list with at least one of the finger's keys, within the
+/// length limits when any are
+fn real_words(
+    keys: &[char],
+    words: &[String],
+    options: &WordOptions,
+    word_count: usize,
+    rng: &mut impl Rng,
+) -> Option<String> {
+    let on_finger: Vec<&String> = words.iter().filter(|w| w.chars().any(|c| keys.contains(&c))).collect();
+    let mut pool: Vec<&String> = on_finger.iter().copied().filter(|w| options.allows_length(w)).collect();
+    if pool.is_empty() {
+        pool = on_finger;
+    }
+    let mut drill: Vec<&String> = Vec::with_capacity(word_count);
+
+    while drill.len() < word_count {
+        let word = *pool.choose(rng)?;
+        // A one-word pool has nothing else to offer
+        if options.avoid_repeats && pool.len() > 1 && drill.last() == Some(&word) {
+            continue;
         }
+        drill.push(word);
     }
 
-    for key in keys {
-        if key.is_alphabetic() {
-            patterns.push(key.to_string().repeat(3));
+    Some(drill.iter().map(|w| w.as_str()).collect::<Vec<_>>().join(" "))
+}
+
+/// At least half of the letters are the finger's own
+fn drills_finger(word: &str, letters: &[char]) -> bool {
+    let on_finger = word.chars().filter(|c| letters.contains(c)).count();
+    on_finger * 2 >= word.chars().count()
+}
+
+/// Doubled pairs, triples of one key and every three-key sequence, keeping
+/// those that are at least half the finger's own letters
+fn generate_patterns(letters: &[char], anchors: &[char]) -> Vec<String> {
+    let keys: Vec<char> = letters.iter().chain(anchors).copied().collect();
+    let mut patterns = Vec::new();
+
+    for &a in &keys {
+        for &b in &keys {
+            patterns.push(format!("{}{}", a, b).repeat(2));
         }
     }
 
-    for i in 0..keys.len() {
-        for j in 0..keys.len() {
-            for k in 0..keys.len() {
-                if keys[i].is_alphabetic() && keys[j].is_alphabetic() && keys[k].is_alphabetic() {
-                    patterns.push(format!("{}{}{}", keys[i], keys[j], keys[k]));
-                }
+    for key in letters {
+        patterns.push(key.to_string().repeat(3));
+    }
+
+    for &a in &keys {
+        for &b in &keys {
+            for &c in &keys {
+                patterns.push(format!("{}{}{}", a, b, c));
             }
         }
     }
 
+    patterns.retain(|pattern| drills_finger(pattern, letters));
     patterns
 }
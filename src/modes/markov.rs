@@ -0,0 +1,151 @@
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use std::collections::HashMap;
+
+/// Letters of context used to pick the next one
+const ORDER: usize = 2;
+/// Marks the start of a word in the context
+const START: char = '^';
+/// Tries per word before giving up on a constraint
+const ATTEMPTS: usize = 200;
+
+/// The last `ORDER` letters, with shorter contexts padded by `'\0'`
+type Context = [char; ORDER];
+
+/// Character-level Markov chain trained on a word list.
+///
+/// Each letter is picked from what followed the previous `ORDER` letters in
+/// the training words, so generated words share the spelling patterns of the
+/// language. When the allowed letters rule out every continuation seen after
+/// the full context, it backs off to a shorter one.
+pub struct Markov {
+    /// Context (0 to `ORDER` letters) to next letter counts, `None` ending the word
+    transitions: HashMap<Context, Vec<(Option<char>, u32)>>,
+}
+
+impl Markov {
+    /// Train on every word made up only of letters, lowercased
+    pub fn train(words: &[String]) -> Self {
+        let mut counts: HashMap<Context, HashMap<Option<char>, u32>> = HashMap::new();
+
+        for word in words {
+            let word = word.to_lowercase();
+            if !word.chars().all(char::is_alphabetic) {
+                continue;
+            }
+            let mut history: Vec<char> = vec![START; ORDER];
+            for next in word.chars().map(Some).chain([None]) {
+                for k in 0..=ORDER {
+                    *counts.entry(context(&history, k)).or_default().entry(next).or_default() += 1;
+                }
+                history.extend(next);
+            }
+        }
+
+        let transitions = counts
+            .into_iter()
+            .map(|(context, next)| {
+                let mut next: Vec<(Option<char>, u32)> = next.into_iter().collect();
+                // HashMap order varies between runs; seeded output must not
+                next.sort();
+                (context, next)
+            })
+            .collect();
+        Markov { transitions }
+    }
+
+    /// Make up a word of `min..=max` letters using only `allowed` letters,
+    /// retrying until `keep` accepts it. `None` if no such word turns up.
+    pub fn generate(
+        &self,
+        allowed: impl Fn(char) -> bool,
+        keep: impl Fn(&str) -> bool,
+        min: usize,
+        max: usize,
+        rng: &mut impl Rng,
+    ) -> Option<String> {
+        (0..ATTEMPTS).find_map(|_| {
+            self.word(&allowed, min, max, rng)
+                .filter(|word| keep(word))
+        })
+    }
+
+    fn word(
+        &self,
+        allowed: &impl Fn(char) -> bool,
+        min: usize,
+        max: usize,
+        rng: &mut impl Rng,
+    ) -> Option<String> {
+        let mut history: Vec<char> = vec![START; ORDER];
+        let mut len = 0;
+
+        loop {
+            match self.next(&history, allowed, len >= min, len >= max, rng)? {
+                Some(c) => {
+                    history.push(c);
+                    len += 1;
+                }
+                None => return Some(history[ORDER..].iter().collect()),
+            }
+        }
+    }
+
+    fn next(
+        &self,
+        history: &[char],
+        allowed: &impl Fn(char) -> bool,
+        can_end: bool,
+        must_end: bool,
+        rng: &mut impl Rng,
+    ) -> Option<Option<char>> {
+        for k in (0..=ORDER).rev() {
+            let Some(options) = self.transitions.get(&context(history, k)) else {
+                continue;
+            };
+            let options: Vec<&(Option<char>, u32)> = options
+                .iter()
+                .filter(|(next, _)| match next {
+                    Some(c) => !must_end && allowed(*c),
+                    None => can_end,
+                })
+                .collect();
+            if let Ok(dist) = WeightedIndex::new(options.iter().map(|(_, count)| *count)) {
+                return Some(options[dist.sample(rng)].0);
+            }
+        }
+        None
+    }
+}
+
+fn context(history: &[char], k: usize) -> Context {
+    let mut context = ['\0'; ORDER];
+    context[ORDER - k..].copy_from_slice(&history[history.len() - k..]);
+    context
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language::Language;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn stays_within_the_letter_set_and_length() {
+        let markov = Markov::train(&Language::default().words);
+        let mut rng = ChaCha8Rng::seed_from_u64(9);
+        for _ in 0..50 {
+            let word = markov.generate(|c| "rtfgvbaeiou".contains(c), |_| true, 3, 6, &mut rng).unwrap();
+            assert!(word.chars().all(|c| "rtfgvbaeiou".contains(c)), "{}", word);
+            assert!((3..=6).contains(&word.chars().count()), "{}", word);
+        }
+    }
+
+    #[test]
+    fn gives_up_on_impossible_constraints() {
+        let markov = Markov::train(&Language::default().words);
+        let mut rng = ChaCha8Rng::seed_from_u64(9);
+        assert_eq!(markov.generate(|c| c == 'ж', |_| true, 3, 6, &mut rng), None);
+    }
+}
@@ -1,11 +1,20 @@
+pub mod code;
+pub mod custom;
 pub mod finger_drill;
+pub mod markov;
 pub mod normal;
 pub mod weak_letter;
+pub mod words;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
 use crate::finger_map::Finger;
+use crate::language::Language;
 use crate::stats::ProgressData;
+use words::WordOptions;
 
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize)]
 pub enum PassageLength {
     Short,
     #[default]
@@ -22,6 +31,10 @@ impl PassageLength {
         }
     }
 
+    pub fn all() -> [PassageLength; 3] {
+        [PassageLength::Short, PassageLength::Medium, PassageLength::Long]
+    }
+
     pub fn word_count(&self) -> usize {
         match self {
             PassageLength::Short => 10,
@@ -37,9 +50,18 @@ impl PassageLength {
             PassageLength::Long => "Long",
         }
     }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "short" => Some(PassageLength::Short),
+            "medium" => Some(PassageLength::Medium),
+            "long" => Some(PassageLength::Long),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum Mode {
     Normal,
     WeakLetter,
@@ -55,6 +77,29 @@ impl Mode {
         }
     }
 
+    /// Short, command-line friendly identifier (e.g. `weak`, `left-pinky`)
+    pub fn slug(&self) -> &'static str {
+        match self {
+            Mode::Normal => "normal",
+            Mode::WeakLetter => "weak",
+            Mode::FingerDrill(finger) => finger.slug(),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Mode> {
+        match name.to_ascii_lowercase().as_str() {
+            "normal" => Some(Mode::Normal),
+            "weak" | "weak-letter" | "weak-letters" => Some(Mode::WeakLetter),
+            other => Finger::from_name(other).map(Mode::FingerDrill),
+        }
+    }
+
+    pub fn all() -> Vec<Mode> {
+        let mut modes = vec![Mode::Normal, Mode::WeakLetter];
+        modes.extend(Finger::all().into_iter().map(Mode::FingerDrill));
+        modes
+    }
+
     pub fn next(&self) -> Mode {
         match self {
             Mode::Normal => Mode::WeakLetter,
@@ -90,11 +135,166 @@ impl Mode {
         }
     }
 
-    pub fn generate_text(&self, progress: &ProgressData, word_count: usize) -> String {
+    /// Whether passages are drawn from the selected word list
+    pub fn uses_words(&self) -> bool {
+        matches!(self, Mode::Normal | Mode::WeakLetter)
+    }
+
+    /// Generate a passage. The same seed, word list and options always give
+    /// the same text (weak-letter passages also depend on your history).
+    pub fn generate_text(
+        &self,
+        progress: &ProgressData,
+        language: &Language,
+        options: &WordOptions,
+        word_count: usize,
+        seed: u64,
+    ) -> String {
+        // ChaCha's output is fixed across platforms and rand releases, unlike StdRng
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
         match self {
-            Mode::Normal => normal::generate_text(word_count),
-            Mode::WeakLetter => weak_letter::generate_text(progress, word_count),
-            Mode::FingerDrill(finger) => finger_drill::generate_text(*finger, word_count),
+            Mode::Normal => normal::generate_text(language, options, word_count, &mut rng),
+            Mode::WeakLetter => {
+                weak_letter::generate_text(progress, language, options, word_count, &mut rng)
+            }
+            Mode::FingerDrill(finger) => {
+                finger_drill::generate_text(*finger, &language.words, options, word_count, &mut rng)
+            }
         }
     }
 }
+
+/// A fresh seed, kept to six digits so it is easy to read out and type
+pub fn random_seed() -> u64 {
+    rand::thread_rng().gen_range(0..1_000_000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use words::Difficulty;
+
+    fn generate(mode: Mode, options: &WordOptions, seed: u64) -> String {
+        mode.generate_text(&ProgressData { results: Vec::new() }, &Language::default(), options, 25, seed)
+    }
+
+    #[test]
+    fn same_seed_gives_same_passage() {
+        let options = WordOptions::default();
+        for mode in Mode::all() {
+            assert_eq!(generate(mode, &options, 1234), generate(mode, &options, 1234), "{}", mode.name());
+        }
+    }
+
+    #[test]
+    fn different_seeds_give_different_passages() {
+        let options = WordOptions::default();
+        for mode in [Mode::Normal, Mode::WeakLetter, Mode::FingerDrill(Finger::LeftIndex)] {
+            assert_ne!(generate(mode, &options, 1), generate(mode, &options, 2), "{}", mode.name());
+        }
+    }
+
+    #[test]
+    fn word_options_change_the_passage() {
+        let long = WordOptions { min_length: Some(6), ..WordOptions::default() };
+        assert_ne!(generate(Mode::Normal, &WordOptions::default(), 7), generate(Mode::Normal, &long, 7));
+    }
+
+    #[test]
+    fn avoids_immediate_repeats() {
+        let options = WordOptions { avoid_repeats: true, ..WordOptions::default() };
+        for seed in 0..20 {
+            let passage = generate(Mode::Normal, &options, seed);
+            let words: Vec<&str> = passage.split(' ').collect();
+            assert!(words.windows(2).all(|pair| pair[0] != pair[1]), "{}", passage);
+        }
+    }
+
+    #[test]
+    fn respects_word_length_limits() {
+        let options = WordOptions {
+            difficulty: Difficulty::Top1k,
+            min_length: Some(4),
+            max_length: Some(6),
+            ..WordOptions::default()
+        };
+        let passage = generate(Mode::Normal, &options, 3);
+        assert!(passage.split(' ').all(|w| (4..=6).contains(&w.chars().count())), "{}", passage);
+    }
+
+    #[test]
+    fn finger_drills_stay_on_the_finger() {
+        use crate::finger_map::{finger_for_key, row_for_key, Row};
+        // The right pinky drills real words instead, see below
+        for finger in Finger::all().into_iter().filter(|&f| f != Finger::RightPinky) {
+            let keys = crate::finger_map::get_keys_for_finger(finger);
+            let passage = generate(Mode::FingerDrill(finger), &WordOptions::default(), 5);
+            for word in passage.split(' ') {
+                for c in word.chars() {
+                    let anchor = row_for_key(c) == Some(Row::Home)
+                        && finger_for_key(c).is_some_and(|f| f.hand() == finger.hand());
+                    assert!(keys.contains(&c) || anchor, "{} in {} ({})", c, word, finger.name());
+                }
+                let on_finger = word.chars().filter(|c| keys.contains(c)).count();
+                assert!(on_finger * 2 >= word.chars().count(), "{} ({})", word, finger.name());
+            }
+        }
+    }
+
+    #[test]
+    fn the_right_pinky_drills_real_words() {
+        let keys = crate::finger_map::get_keys_for_finger(Finger::RightPinky);
+        let language = Language::default();
+        let passage = generate(Mode::FingerDrill(Finger::RightPinky), &WordOptions::default(), 5);
+        for word in passage.split(' ') {
+            assert!(language.words.iter().any(|known| known == word), "{}", word);
+            assert!(word.chars().any(|c| keys.contains(&c)), "{}", word);
+        }
+    }
+
+    #[test]
+    fn finger_drills_fall_back_to_key_patterns() {
+        let cyrillic = Language { name: "russian".to_string(), words: vec!["мир".to_string(), "дом".to_string()], ranked: 2 };
+        let passage = Mode::FingerDrill(Finger::LeftRing).generate_text(
+            &ProgressData { results: Vec::new() },
+            &cyrillic,
+            &WordOptions::default(),
+            10,
+            1,
+        );
+        // The ring finger's own keys, anchored on the left home row
+        assert!(passage.chars().all(|c| "wsxadfg ".contains(c)), "{}", passage);
+    }
+
+    #[test]
+    fn mixes_in_made_up_words() {
+        let language = Language::default();
+        let options = WordOptions { pseudo_words: true, ..WordOptions::default() };
+        let passage = generate(Mode::Normal, &options, 11);
+        assert!(passage.split(' ').any(|w| !language.words.iter().any(|known| known == w)), "{}", passage);
+    }
+
+    #[test]
+    fn passages_are_stable_across_releases() {
+        // Seeds are shared between players, so the generator must not drift
+        assert_eq!(
+            generate(Mode::FingerDrill(Finger::LeftIndex), &WordOptions::default(), 42),
+            "fat bat trd grt trt tart bav fat atr tar tart star frd grt tart trt grt star agart trt aft \
+             dart grd bar start"
+        );
+        // Real words, for fingers whose letters make no made-up words
+        assert_eq!(
+            generate(Mode::FingerDrill(Finger::RightPinky), &WordOptions::default(), 42),
+            "group paper picture page spell don't picture people up play plant group plant play open keep \
+             up picture up play open keep open point up"
+        );
+    }
+
+    #[test]
+    fn word_passages_are_stable_across_releases() {
+        assert_eq!(
+            generate(Mode::Normal, &WordOptions::default(), 42),
+            "her last in this to the to him which be I much start as there be he the in the of an the to then"
+        );
+    }
+}
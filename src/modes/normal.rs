@@ -1,22 +1,13 @@
-use rand::seq::SliceRandom;
+use rand::Rng;
 
-const WORDS: &[&str] = &[
-    "the", "be", "to", "of", "and", "a", "in", "that", "have", "I", "it", "for", "not", "on",
-    "with", "he", "as", "you", "do", "at", "this", "but", "his", "by", "from", "they", "we",
-    "say", "her", "she", "or", "an", "will", "my", "one", "all", "would", "there", "their",
-    "what", "so", "up", "out", "if", "about", "who", "get", "which", "go", "me", "when", "make",
-    "can", "like", "time", "no", "just", "him", "know", "take", "people", "into", "year", "your",
-    "good", "some", "could", "them", "see", "other", "than", "then", "now", "look", "only",
-    "come", "its", "over", "think", "also", "back", "after", "use", "two", "how", "our", "work",
-    "first", "well", "way", "even", "new", "want", "because", "any", "these", "give", "day",
-    "most", "us",
-];
+use super::words::{self, WordOptions};
+use crate::language::Language;
 
-pub fn generate_text(word_count: usize) -> String {
-    let mut rng = rand::thread_rng();
-
-    (0..word_count)
-        .map(|_| *WORDS.choose(&mut rng).unwrap())
-        .collect::<Vec<_>>()
-        .join(" ")
+pub fn generate_text(
+    language: &Language,
+    options: &WordOptions,
+    word_count: usize,
+    rng: &mut impl Rng,
+) -> String {
+    words::generate(language, options, |_| true, word_count, rng)
 }
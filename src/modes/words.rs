@@ -0,0 +1,391 @@
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::markov::Markov;
+use crate::language::Language;
+
+/// One in this many words is made up when made-up words are mixed in
+const PSEUDO_WORD_SHARE: u32 = 3;
+
+/// Word lists put their most common words first; the tier picks which part of
+/// the list to draw from. A tier needs the list to be ranked far enough to
+/// tell which words belong to it (see [`Difficulty::ranks`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+    #[default]
+    Top200,
+    Top1k,
+    Top10k,
+    /// Everything past the top 1k (or the less common half of shorter, fully
+    /// ranked lists)
+    Rare,
+}
+
+impl Difficulty {
+    pub fn all() -> [Difficulty; 4] {
+        [Difficulty::Top200, Difficulty::Top1k, Difficulty::Top10k, Difficulty::Rare]
+    }
+
+    pub fn next(&self) -> Difficulty {
+        match self {
+            Difficulty::Top200 => Difficulty::Top1k,
+            Difficulty::Top1k => Difficulty::Top10k,
+            Difficulty::Top10k => Difficulty::Rare,
+            Difficulty::Rare => Difficulty::Top200,
+        }
+    }
+
+    pub fn previous(&self) -> Difficulty {
+        match self {
+            Difficulty::Top200 => Difficulty::Rare,
+            Difficulty::Top1k => Difficulty::Top200,
+            Difficulty::Top10k => Difficulty::Top1k,
+            Difficulty::Rare => Difficulty::Top10k,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Top200 => "Top 200",
+            Difficulty::Top1k => "Top 1k",
+            Difficulty::Top10k => "Top 10k",
+            Difficulty::Rare => "Rare",
+        }
+    }
+
+    pub fn slug(&self) -> &'static str {
+        match self {
+            Difficulty::Top200 => "top200",
+            Difficulty::Top1k => "top1k",
+            Difficulty::Top10k => "top10k",
+            Difficulty::Rare => "rare",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        match name.to_ascii_lowercase().replace([' ', '_', '-'], "").as_str() {
+            "top200" | "200" => Some(Difficulty::Top200),
+            "top1k" | "1k" | "top1000" => Some(Difficulty::Top1k),
+            "top10k" | "10k" | "top10000" => Some(Difficulty::Top10k),
+            "rare" => Some(Difficulty::Rare),
+            _ => None,
+        }
+    }
+
+    /// Range of list positions (0-based) in the tier, for a list of `len`
+    /// words whose first `ranked` are in frequency order. `None` when the
+    /// list is not ranked far enough to tell which words are in the tier.
+    pub fn ranks(&self, len: usize, ranked: usize) -> Option<std::ops::Range<usize>> {
+        // A fully ranked list shorter than the tier is the whole tier
+        let top = |n: usize| (ranked >= n.min(len)).then(|| 0..n.min(len));
+        match self {
+            Difficulty::Top200 => top(200),
+            Difficulty::Top1k => top(1_000),
+            Difficulty::Top10k => top(10_000),
+            Difficulty::Rare if len > 1_000 => (ranked >= 1_000).then_some(1_000..len),
+            Difficulty::Rare => (ranked == len).then_some(len / 2..len),
+        }
+    }
+
+    /// Whether `language` is ranked far enough for this tier
+    pub fn available(&self, language: &Language) -> bool {
+        self.ranks(language.words.len(), language.ranked).is_some()
+    }
+}
+
+/// How words are picked for generated passages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WordOptions {
+    pub difficulty: Difficulty,
+    /// Never pick the same word twice in a row
+    pub avoid_repeats: bool,
+    /// Shortest word allowed, in letters
+    pub min_length: Option<usize>,
+    /// Longest word allowed, in letters
+    pub max_length: Option<usize>,
+    /// Mix in made-up words spelled like the language's own
+    pub pseudo_words: bool,
+}
+
+impl Default for WordOptions {
+    fn default() -> Self {
+        WordOptions {
+            difficulty: Difficulty::default(),
+            avoid_repeats: true,
+            min_length: None,
+            max_length: None,
+            pseudo_words: false,
+        }
+    }
+}
+
+impl WordOptions {
+    /// Compact form for passage codes and exports, e.g. `top1k,min3,madeup`.
+    /// Options left at their defaults are omitted, except the difficulty.
+    pub fn tags(&self) -> String {
+        let mut tags = vec![self.difficulty.slug().to_string()];
+        tags.extend(self.min_length.map(|min| format!("min{}", min)));
+        tags.extend(self.max_length.map(|max| format!("max{}", max)));
+        if self.pseudo_words {
+            tags.push("madeup".to_string());
+        }
+        if !self.avoid_repeats {
+            tags.push("repeats".to_string());
+        }
+        tags.join(",")
+    }
+
+    pub fn from_tags(tags: &str) -> Result<WordOptions, String> {
+        let mut tags = tags.split(',').map(|tag| tag.trim().to_ascii_lowercase());
+        let difficulty = tags.next().unwrap_or_default();
+        let mut options = WordOptions {
+            difficulty: Difficulty::from_name(&difficulty)
+                .ok_or_else(|| format!("unknown difficulty '{}'", difficulty))?,
+            ..WordOptions::default()
+        };
+        for tag in tags {
+            let length = |prefix: &str| tag.strip_prefix(prefix).and_then(|n| n.parse::<usize>().ok());
+            match tag.as_str() {
+                "madeup" => options.pseudo_words = true,
+                "repeats" => options.avoid_repeats = false,
+                _ if length("min").is_some() => options.min_length = length("min"),
+                _ if length("max").is_some() => options.max_length = length("max"),
+                _ => return Err(format!("unknown word option '{}'", tag)),
+            }
+        }
+        Ok(options)
+    }
+
+    pub(super) fn allows_length(&self, word: &str) -> bool {
+        let len = word.graphemes(true).count();
+        self.min_length.is_none_or(|min| len >= min) && self.max_length.is_none_or(|max| len <= max)
+    }
+}
+
+/// Build a passage of `count` words from a word list.
+///
+/// Words in the list's ranked part are weighted by Zipf's law (a word's
+/// weight is 1 / its rank), so common words come up about as often as they do
+/// in real text. Words past it share the weight Zipf's law gives their
+/// positions evenly, since their order says nothing about which of them is
+/// more common. Only words
+/// in the difficulty tier, within the length limits and accepted by `keep`
+/// are used. If the list is not ranked far enough for the tier, the whole
+/// list is used instead. If nothing qualifies, `keep` is dropped first, then
+/// the length limits, then the tier.
+///
+/// With `pseudo_words` on, about one word in three is instead made up by a
+/// Markov chain trained on the list, within the length limits and accepted
+/// by `keep` when possible.
+pub fn generate(
+    language: &Language,
+    options: &WordOptions,
+    keep: impl Fn(&str) -> bool,
+    count: usize,
+    rng: &mut impl Rng,
+) -> String {
+    let words = &language.words;
+    let tier = options.difficulty.ranks(words.len(), language.ranked).unwrap_or(0..words.len());
+    let ranked = || words.iter().map(String::as_str).enumerate();
+
+    let mut pool: Vec<(usize, &str)> = ranked()
+        .filter(|(rank, word)| tier.contains(rank) && options.allows_length(word) && keep(word))
+        .collect();
+    if pool.is_empty() {
+        pool = ranked()
+            .filter(|(rank, word)| tier.contains(rank) && options.allows_length(word))
+            .collect();
+    }
+    if pool.is_empty() {
+        pool = ranked().filter(|(rank, _)| tier.contains(rank)).collect();
+    }
+    if pool.is_empty() {
+        pool = ranked().collect();
+    }
+
+    let mut passage = sample(&pool, language.ranked, options.avoid_repeats, count, rng);
+    if options.pseudo_words {
+        mix_in_pseudo_words(&mut passage, words, options, &keep, rng);
+    }
+    passage.join(" ")
+}
+
+fn mix_in_pseudo_words(
+    passage: &mut [String],
+    words: &[String],
+    options: &WordOptions,
+    keep: &impl Fn(&str) -> bool,
+    rng: &mut impl Rng,
+) {
+    let markov = Markov::train(words);
+    let min = options.min_length.unwrap_or(3);
+    let max = options.max_length.unwrap_or(9).max(min);
+
+    for i in 0..passage.len() {
+        if !rng.gen_ratio(1, PSEUDO_WORD_SHARE) {
+            continue;
+        }
+        let neighbours = [i.checked_sub(1), Some(i + 1)].map(|j| j.and_then(|j| passage.get(j)).cloned());
+        let made_up = markov.generate(
+            |_| true,
+            |w| keep(w) && !(options.avoid_repeats && neighbours.iter().flatten().any(|n| n == w)),
+            min,
+            max,
+            rng,
+        );
+        if let Some(word) = made_up {
+            passage[i] = word;
+        }
+    }
+}
+
+fn sample(
+    pool: &[(usize, &str)],
+    ranked: usize,
+    avoid_repeats: bool,
+    count: usize,
+    rng: &mut impl Rng,
+) -> Vec<String> {
+    let zipf = |rank: usize| 1.0 / (rank as f64 + 1.0);
+    let unranked: Vec<f64> = pool.iter().filter(|(rank, _)| *rank >= ranked).map(|(rank, _)| zipf(*rank)).collect();
+    let unranked_weight = unranked.iter().sum::<f64>() / unranked.len().max(1) as f64;
+    let weight = |rank: usize| if rank < ranked { zipf(rank) } else { unranked_weight };
+    let Ok(dist) = WeightedIndex::new(pool.iter().map(|(rank, _)| weight(*rank))) else {
+        return Vec::new();
+    };
+    let mut passage: Vec<&str> = Vec::with_capacity(count);
+
+    while passage.len() < count {
+        let word = pool[dist.sample(rng)].1;
+        // A one-word pool has nothing else to offer
+        if avoid_repeats && pool.len() > 1 && passage.last() == Some(&word) {
+            continue;
+        }
+        passage.push(word);
+    }
+
+    passage.into_iter().map(str::to_string).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn language(words: &str, ranked: usize) -> Language {
+        let words: Vec<String> = words.split_whitespace().map(str::to_string).collect();
+        Language { name: "test".to_string(), ranked: ranked.min(words.len()), words }
+    }
+
+    fn passage(language: &Language, options: &WordOptions, keep: impl Fn(&str) -> bool) -> Vec<String> {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        generate(language, options, keep, 200, &mut rng).split(' ').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn tiers_cover_their_part_of_the_list() {
+        assert_eq!(Difficulty::Top200.ranks(10_000, 10_000), Some(0..200));
+        assert_eq!(Difficulty::Top1k.ranks(10_000, 1_000), Some(0..1_000));
+        assert_eq!(Difficulty::Top10k.ranks(20_000, 20_000), Some(0..10_000));
+        assert_eq!(Difficulty::Rare.ranks(10_000, 1_000), Some(1_000..10_000));
+        // Fully ranked short lists are taken whole, or split in half for rare words
+        assert_eq!(Difficulty::Top1k.ranks(180, 180), Some(0..180));
+        assert_eq!(Difficulty::Rare.ranks(180, 180), Some(90..180));
+        assert_eq!(Difficulty::Rare.ranks(1_000, 1_000), Some(500..1_000));
+    }
+
+    #[test]
+    fn tiers_need_a_list_ranked_far_enough() {
+        // Ranked to 100: the top 200 and rare words can't be told apart
+        assert_eq!(Difficulty::Top200.ranks(1_000, 100), None);
+        assert_eq!(Difficulty::Top1k.ranks(1_000, 100), None);
+        assert_eq!(Difficulty::Rare.ranks(1_000, 100), None);
+        assert_eq!(Difficulty::Rare.ranks(10_000, 999), None);
+        assert_eq!(Difficulty::Top10k.ranks(20_000, 5_000), None);
+        assert_eq!(Difficulty::Top200.ranks(10_000, 200), Some(0..200));
+        assert!(!Difficulty::Top200.available(&Language::default()));
+
+        // The whole list is used instead, so every word can come up
+        let list = language("one two three four five six", 2);
+        let options = WordOptions { difficulty: Difficulty::Rare, ..WordOptions::default() };
+        let mut used = passage(&list, &options, |_| true);
+        used.sort();
+        used.dedup();
+        assert_eq!(used, ["five", "four", "one", "six", "three", "two"]);
+    }
+
+    #[test]
+    fn options_are_relaxed_in_order_when_nothing_fits() {
+        let list = language("a bb ccc dddd eeeee ffffff", 6);
+        let options = WordOptions { difficulty: Difficulty::Rare, min_length: Some(5), ..WordOptions::default() };
+        let used = |passage: Vec<String>| {
+            let mut used: Vec<String> = passage;
+            used.sort();
+            used.dedup();
+            used
+        };
+
+        // Everything fits: rare words of at least five letters, kept by `keep`
+        assert_eq!(used(passage(&list, &options, |w| w.starts_with('f'))), ["ffffff"]);
+        // `keep` is dropped first
+        assert_eq!(used(passage(&list, &options, |w| w.starts_with('a'))), ["eeeee", "ffffff"]);
+        // Then the length limits
+        let too_long = WordOptions { min_length: Some(9), ..options };
+        assert_eq!(used(passage(&list, &too_long, |_| true)), ["dddd", "eeeee", "ffffff"]);
+        // Then the tier, when it is empty
+        let empty = language("a", 1);
+        assert_eq!(used(passage(&empty, &WordOptions { min_length: Some(3), ..options }, |_| true)), ["a"]);
+    }
+
+    #[test]
+    fn ranked_words_are_drawn_by_zipfs_law() {
+        let words: Vec<String> = (0..1_000).map(|rank| format!("w{}", rank)).collect();
+        let list = language(&words.join(" "), 1_000);
+        let options = WordOptions { difficulty: Difficulty::Top1k, avoid_repeats: false, ..WordOptions::default() };
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let text = generate(&list, &options, |_| true, 20_000, &mut rng);
+        let passage: Vec<usize> = text.split(' ').map(|w| w[1..].parse().unwrap()).collect();
+
+        let share = |ranks: std::ops::Range<usize>| {
+            passage.iter().filter(|rank| ranks.contains(rank)).count() as f64 / passage.len() as f64
+        };
+        // 1 / H(1000) ≈ 13% for the top word, against 0.03% for rank 500
+        assert!((0.11..0.16).contains(&share(0..1)), "{}", share(0..1));
+        assert!(share(500..501) < 0.002, "{}", share(500..501));
+        // The top 10 words take about 39%, the bottom half about 9%
+        assert!(share(0..10) > 0.35 && share(500..1_000) < 0.12, "{} {}", share(0..10), share(500..1_000));
+    }
+
+    #[test]
+    fn top_ranks_dominate_a_large_sample() {
+        let language = Language::default();
+        let options = WordOptions { avoid_repeats: false, ..WordOptions::default() };
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let text = generate(&language, &options, |_| true, 20_000, &mut rng);
+        let passage: Vec<&str> = text.split(' ').collect();
+
+        let count = |word: &str| passage.iter().filter(|w| **w == word).count();
+        // Zipf's law over the ranked prefix makes "the" the most drawn word
+        assert!(count("the") > 1_500, "{}", count("the"));
+        assert!(count("the") > count("be") && count("be") > count("because"));
+        let top_100 = passage.iter().filter(|w| language.words[..100].iter().any(|top| top == *w)).count();
+        assert!(top_100 * 2 > passage.len(), "{} of {}", top_100, passage.len());
+    }
+
+    #[test]
+    fn unranked_words_share_an_even_weight() {
+        let list = language("one two three four", 0);
+        let options = WordOptions { difficulty: Difficulty::Top200, avoid_repeats: false, ..WordOptions::default() };
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let text = generate(&list, &options, |_| true, 4_000, &mut rng);
+        for word in ["one", "two", "three", "four"] {
+            let count = text.split(' ').filter(|w| *w == word).count();
+            assert!((800..1_200).contains(&count), "{} came up {} times", word, count);
+        }
+    }
+}
@@ -0,0 +1,269 @@
+//! Screen-reader mode: plain lines of text instead of the full-screen
+//! interface.
+//!
+//! Nothing is redrawn or positioned, so everything printed stays in the
+//! terminal's scrollback in reading order. The passage is printed once,
+//! then mistakes, progress and the results are announced as they happen.
+//! Keys go through the same `App` as the full-screen interface, so results,
+//! streaks and achievements are recorded as usual.
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use std::io::{self, Write};
+
+use crate::typing::GraphemeState;
+use crate::App;
+
+const SPACE: &str = " ";
+/// Progress is announced this many times per passage
+const PROGRESS_STEPS: usize = 4;
+
+const HELP: &str = "ratatap screen reader mode. Keys: Ctrl+R reads the rest of the passage, \
+Enter ends the test early, Tab changes mode, Ctrl+L changes length, Escape quits.";
+
+pub fn run(app: &mut App) -> io::Result<()> {
+    enable_raw_mode()?;
+    let result = run_loop(app);
+    disable_raw_mode()?;
+    result
+}
+
+/// What announcements are worked out from: the state before a key
+struct Snapshot {
+    text: String,
+    position: usize,
+    keystroke_errors: usize,
+    overflow: usize,
+    finished: bool,
+}
+
+impl Snapshot {
+    fn of(app: &App) -> Self {
+        Snapshot {
+            text: app.session.text(),
+            position: app.session.position(),
+            keystroke_errors: app.session.keystroke_errors(),
+            overflow: app.session.overflow_count(),
+            finished: app.end_time.is_some(),
+        }
+    }
+}
+
+/// Keeps track of what has already been said about the current passage
+#[derive(Default)]
+struct Announcer {
+    progress_step: usize,
+    told_to_fix: bool,
+}
+
+fn run_loop(app: &mut App) -> io::Result<()> {
+    say(HELP)?;
+    let mut announcer = Announcer::default();
+    announcer.passage(app)?;
+
+    loop {
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c') if ctrl => return Ok(()),
+            KeyCode::Char('r') if ctrl => {
+                say(&format!("Remaining: {}", remaining(app)))?;
+                continue;
+            }
+            _ if !forwarded(key.code, key.modifiers) => continue,
+            _ => {}
+        }
+
+        let before = Snapshot::of(app);
+        app.handle_key(key.code, key.modifiers);
+        if app.should_quit {
+            return Ok(());
+        }
+        announcer.changes(app, &before)?;
+    }
+}
+
+/// Keys that make sense without a screen: typing, editing and switching
+/// passages. Everything else opens a panel there would be no way to read.
+fn forwarded(code: KeyCode, modifiers: KeyModifiers) -> bool {
+    let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+    match code {
+        KeyCode::Char('w' | 'h' | 'l') if ctrl => true,
+        KeyCode::Char(_) => !ctrl && !modifiers.contains(KeyModifiers::ALT),
+        KeyCode::Backspace | KeyCode::Enter | KeyCode::Esc | KeyCode::Tab | KeyCode::BackTab => true,
+        _ => false,
+    }
+}
+
+impl Announcer {
+    /// Introduce a new passage
+    fn passage(&mut self, app: &mut App) -> io::Result<()> {
+        *self = Announcer::default();
+        let words = word_count(app.session.target());
+        let heading = match &app.custom_passage {
+            Some(passage) => format!("File {}, {} words.", passage.name, words),
+            None => format!("{} mode, {} passage, {} words.", app.mode.name(), app.passage_length.name(), words),
+        };
+        say(&heading)?;
+        say(&format!("Passage: {}", app.session.text()))?;
+        self.notifications(app)
+    }
+
+    fn changes(&mut self, app: &mut App, before: &Snapshot) -> io::Result<()> {
+        let finished = app.end_time.is_some();
+        if app.session.text() != before.text || (before.finished && !finished) {
+            return self.passage(app);
+        }
+        if finished && !before.finished {
+            return self.results(app);
+        }
+
+        let session = &app.session;
+        let position = session.position();
+        if position > before.position {
+            let mut skipped = false;
+            for i in before.position..position {
+                match session.state(i) {
+                    GraphemeState::Incorrect => say(&format!(
+                        "Wrong: expected {}, typed {}.",
+                        describe(&session.target()[i]),
+                        describe(session.typed(i).unwrap_or_default())
+                    ))?,
+                    GraphemeState::Skipped if !skipped => {
+                        skipped = true;
+                        say("Skipped the rest of the word.")?;
+                    }
+                    _ => {}
+                }
+            }
+            self.progress(app)?;
+        } else if position < before.position {
+            say(&format!("Back to {}.", next_up(app)))?;
+        } else if session.overflow_count() != before.overflow {
+            match session.overflow(position).last() {
+                Some(extra) if session.overflow_count() > before.overflow => {
+                    say(&format!("Extra letter {} after the word.", describe(extra)))?
+                }
+                _ => say("Removed an extra letter.")?,
+            }
+        } else if session.keystroke_errors() > before.keystroke_errors
+            && let Some(expected) = session.current()
+        {
+            // Strict input modes reject the key instead of moving on
+            say(&format!("Wrong key, expected {}.", describe(expected)))?;
+        }
+
+        if app.end_time.is_none() && app.session.at_end() && app.session.finish_blocked() && !self.told_to_fix {
+            self.told_to_fix = true;
+            say("End of passage. Fix the remaining errors to finish.")?;
+        }
+        Ok(())
+    }
+
+    /// Announce each quarter of the passage once
+    fn progress(&mut self, app: &App) -> io::Result<()> {
+        let target = app.session.target();
+        let total = word_count(target);
+        let done = target[..app.session.position()].iter().filter(|g| *g == SPACE).count();
+        let step = done * PROGRESS_STEPS / total.max(1);
+        if step > self.progress_step && step < PROGRESS_STEPS {
+            self.progress_step = step;
+            say(&format!(
+                "{} of {} words, {:.0} words per minute, {:.0}% accuracy.",
+                done, total, app.current_wpm, app.current_accuracy
+            ))?;
+        }
+        Ok(())
+    }
+
+    fn results(&mut self, app: &mut App) -> io::Result<()> {
+        let errors: usize = app.session.char_errors().values().sum();
+        let seconds = match (app.start_time, app.end_time) {
+            (Some(start), Some(end)) => end.duration_since(start).as_secs_f64(),
+            _ => 0.0,
+        };
+        say(&format!(
+            "Finished. {:.0} words per minute, {:.1}% accuracy, {:.0}% of words correct, {} {} in {:.1} seconds.",
+            app.current_wpm,
+            app.current_accuracy,
+            app.word_accuracy,
+            errors,
+            if errors == 1 { "error" } else { "errors" },
+            seconds
+        ))?;
+        if let Some(best) = &app.new_personal_best {
+            match best.previous_wpm {
+                Some(previous) => say(&format!("New personal best, up from {:.0} words per minute.", previous))?,
+                None => say("First result for this mode and length.")?,
+            }
+        }
+        self.notifications(app)?;
+        say("Press Enter for a new passage, R to type this one again, or Escape to quit.")
+    }
+
+    /// Read out and clear achievement notifications
+    fn notifications(&mut self, app: &mut App) -> io::Result<()> {
+        for toast in app.toasts.drain(..) {
+            say(&format!("{}: {}", toast.title, toast.message))?;
+        }
+        Ok(())
+    }
+}
+
+/// The passage from the cursor on
+fn remaining(app: &App) -> String {
+    let rest = app.session.target()[app.session.position()..].concat();
+    if rest.is_empty() { "nothing, the passage is typed".to_string() } else { rest }
+}
+
+/// Where typing picks up, e.g. "word 3, next t"
+fn next_up(app: &App) -> String {
+    let target = app.session.target();
+    let position = app.session.position();
+    let word = target[..position].iter().filter(|g| *g == SPACE).count() + 1;
+    match target.get(position) {
+        Some(next) => format!("word {}, next {}", word, describe(next)),
+        None => format!("word {}", word),
+    }
+}
+
+fn word_count(target: &[String]) -> usize {
+    target.concat().split_whitespace().count()
+}
+
+/// A character as it should be read out
+fn describe(grapheme: &str) -> String {
+    match grapheme {
+        SPACE => "space".to_string(),
+        "" => "nothing".to_string(),
+        _ => format!("\"{}\"", grapheme),
+    }
+}
+
+/// Print one line. Raw mode turns off newline translation, hence the `\r`.
+fn say(line: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "{}\r\n", line)?;
+    stdout.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_forwards_keys_that_need_no_screen() {
+        assert!(forwarded(KeyCode::Char('A'), KeyModifiers::SHIFT));
+        assert!(forwarded(KeyCode::Char('w'), KeyModifiers::CONTROL));
+        assert!(forwarded(KeyCode::Backspace, KeyModifiers::ALT));
+        // Ctrl+F, Ctrl+P and friends open panels
+        assert!(!forwarded(KeyCode::Char('f'), KeyModifiers::CONTROL));
+        assert!(!forwarded(KeyCode::Char('p'), KeyModifiers::CONTROL));
+        assert!(!forwarded(KeyCode::Up, KeyModifiers::NONE));
+    }
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::goals::DailyGoal;
+use crate::heatmap::{HeatmapMode, HeatmapScope, DEFAULT_IMPROVEMENT_DAYS};
+use crate::input_mode::InputMode;
+use crate::language::DEFAULT_LANGUAGE;
+use crate::modes::words::WordOptions;
+use crate::ui::caret::CaretStyle;
+use crate::ui::color_depth::ColorDepth;
+use crate::ui::keyboard_layout::KeyboardLayout;
+use crate::ui::panels::{LayoutPreset, Panels};
+use crate::ui::passage::ErrorCue;
+
+/// User preferences persisted across sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Settings {
+    #[serde(default)]
+    pub daily_goal: DailyGoal,
+    #[serde(default)]
+    pub input_mode: InputMode,
+    #[serde(default = "default_language")]
+    pub language: String,
+    #[serde(default)]
+    pub words: WordOptions,
+    #[serde(default)]
+    pub heatmap: HeatmapMode,
+    #[serde(default)]
+    pub heatmap_scope: HeatmapScope,
+    /// Days the improvement heatmap counts as recent
+    #[serde(default = "default_improvement_days")]
+    pub improvement_days: u32,
+    /// Tint the keyboard by finger and show which finger types the next key
+    #[serde(default)]
+    pub finger_overlay: bool,
+    /// Physical shape of the on-screen keyboard
+    #[serde(default)]
+    pub keyboard_layout: KeyboardLayout,
+    /// How mistakes are marked besides their color
+    #[serde(default)]
+    pub error_cue: ErrorCue,
+    #[serde(default)]
+    pub caret: CaretStyle,
+    /// Glide the caret to each new position instead of jumping there
+    #[serde(default)]
+    pub smooth_caret: bool,
+    #[serde(default)]
+    pub layout: LayoutPreset,
+    /// Panels shown in the full and compact layouts
+    #[serde(default)]
+    pub panels: Panels,
+    /// Start in screen-reader mode instead of the full-screen interface
+    #[serde(default)]
+    pub screen_reader: bool,
+    /// Colors the terminal can show; detected from the environment when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub colors: Option<ColorDepth>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            daily_goal: DailyGoal::default(),
+            input_mode: InputMode::default(),
+            language: default_language(),
+            words: WordOptions::default(),
+            heatmap: HeatmapMode::default(),
+            heatmap_scope: HeatmapScope::default(),
+            improvement_days: DEFAULT_IMPROVEMENT_DAYS,
+            finger_overlay: false,
+            keyboard_layout: KeyboardLayout::default(),
+            error_cue: ErrorCue::default(),
+            caret: CaretStyle::default(),
+            smooth_caret: false,
+            layout: LayoutPreset::default(),
+            panels: Panels::default(),
+            screen_reader: false,
+            colors: None,
+        }
+    }
+}
+
+fn default_language() -> String {
+    DEFAULT_LANGUAGE.to_string()
+}
+
+fn default_improvement_days() -> u32 {
+    DEFAULT_IMPROVEMENT_DAYS
+}
+
+impl Settings {
+    fn get_path() -> PathBuf {
+        let mut path = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        path.push(".ratatap");
+        path.push("settings.json");
+        path
+    }
+
+    pub fn load() -> Self {
+        let path = Self::get_path();
+        if path.exists()
+            && let Ok(data) = fs::read_to_string(&path)
+            && let Ok(settings) = serde_json::from_str(&data)
+        {
+            return settings;
+        }
+        Settings::default()
+    }
+
+    pub fn save(&self) {
+        let path = Self::get_path();
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(data) = serde_json::to_string_pretty(self) {
+            let _ = fs::write(path, data);
+        }
+    }
+}
@@ -3,7 +3,11 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::{error::Error, fs, path::PathBuf};
 
-#[derive(Serialize, Deserialize, Clone)]
+use crate::input_mode::InputMode;
+use crate::modes::words::{Difficulty, WordOptions};
+use crate::modes::{Mode, PassageLength};
+
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct TestResult {
     pub wpm: f64,
     pub accuracy: f64,
@@ -11,6 +15,40 @@ pub struct TestResult {
     pub duration_secs: u64,
     #[serde(default)]
     pub char_errors: HashMap<char, usize>,
+    /// Keystrokes and timing per target character (empty for older and imported results)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub key_stats: HashMap<char, KeyStats>,
+    /// Mode the test was taken in (`None` for custom passages and older results)
+    #[serde(default)]
+    pub mode: Option<Mode>,
+    #[serde(default)]
+    pub length: Option<PassageLength>,
+    #[serde(default)]
+    pub input_mode: InputMode,
+    /// Percentage of attempted words typed correctly (`None` for older and imported results)
+    #[serde(default)]
+    pub word_accuracy: Option<f64>,
+    /// Seed the passage was generated from (`None` for custom passages, older and imported results)
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Word list the passage was drawn from (`None` for custom passages, older and imported results)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Word options the passage was generated with (`None` for custom passages, older and imported results)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub word_options: Option<WordOptions>,
+    /// File a custom passage was loaded from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_passage: Option<String>,
+    /// Timestamp of the first attempt at the same passage, when this test was a retry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_of: Option<DateTime<Utc>>,
+    /// Tool the result was imported from (`None` for tests taken in ratatap)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Identifier assigned by the source tool, used to de-duplicate re-imports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub import_id: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -21,12 +59,11 @@ pub struct ProgressData {
 impl ProgressData {
     pub fn load() -> Self {
         let path = Self::get_path();
-        if path.exists() {
-            if let Ok(data) = fs::read_to_string(&path) {
-                if let Ok(progress) = serde_json::from_str(&data) {
-                    return progress;
-                }
-            }
+        if path.exists()
+            && let Ok(data) = fs::read_to_string(&path)
+            && let Ok(progress) = serde_json::from_str(&data)
+        {
+            return progress;
         }
         ProgressData {
             results: Vec::new(),
@@ -74,6 +111,86 @@ impl ProgressData {
         }
     }
 
+    pub fn best_wpm(&self) -> f64 {
+        self.results.iter().map(|r| r.wpm).fold(0.0, f64::max)
+    }
+
+    pub fn total_practice_secs(&self) -> u64 {
+        self.results.iter().map(|r| r.duration_secs).sum()
+    }
+
+    /// Whether an equivalent result is already recorded. Results carrying a
+    /// source id match on that id; others match on timestamp and WPM.
+    pub fn contains(&self, result: &TestResult) -> bool {
+        self.results.iter().any(|r| match (&r.import_id, &result.import_id) {
+            (Some(a), Some(b)) => a == b && r.source == result.source,
+            _ => {
+                r.timestamp.timestamp() == result.timestamp.timestamp()
+                    && (r.wpm - result.wpm).abs() < 0.01
+            }
+        })
+    }
+
+    /// Every attempt at the passage first typed at `original`, oldest first
+    pub fn attempts(&self, original: DateTime<Utc>) -> Vec<&TestResult> {
+        let mut attempts: Vec<&TestResult> = self
+            .results
+            .iter()
+            .filter(|r| r.timestamp == original || r.retry_of == Some(original))
+            .collect();
+        attempts.sort_by_key(|r| r.timestamp);
+        attempts
+    }
+
+    /// Best WPM result for a record key
+    pub fn personal_best(&self, key: &RecordKey) -> Option<&TestResult> {
+        self.results
+            .iter()
+            .filter(|r| key.matches(r))
+            .max_by(|a, b| a.wpm.total_cmp(&b.wpm))
+    }
+
+    /// Every result that set a new record for a record key, oldest first
+    pub fn personal_best_history(&self, key: &RecordKey) -> Vec<PersonalBest> {
+        let mut history: Vec<PersonalBest> = Vec::new();
+        let mut results: Vec<&TestResult> = self.results.iter().filter(|r| key.matches(r)).collect();
+        results.sort_by_key(|r| r.timestamp);
+
+        for result in results {
+            let previous_wpm = history.last().map(|pb| pb.wpm);
+            if previous_wpm.is_none_or(|prev| result.wpm > prev) {
+                history.push(PersonalBest {
+                    wpm: result.wpm,
+                    accuracy: result.accuracy,
+                    timestamp: result.timestamp,
+                    previous_wpm,
+                });
+            }
+        }
+        history
+    }
+
+    /// Current record for every key that has one, by mode and length and then
+    /// in the order each key was first used
+    pub fn personal_bests(&self) -> Vec<(RecordKey, &TestResult)> {
+        let mut keys: Vec<RecordKey> = Vec::new();
+        for key in self.results.iter().filter_map(RecordKey::of) {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        let (modes, lengths) = (Mode::all(), PassageLength::all());
+        keys.sort_by_key(|key| {
+            let mode = modes.iter().position(|m| *m == key.mode);
+            let length = lengths.iter().position(|l| *l == key.length);
+            (mode, length)
+        });
+
+        keys.into_iter()
+            .filter_map(|key| self.personal_best(&key).map(|best| (key, best)))
+            .collect()
+    }
+
     pub fn get_wpm_history(&self, count: usize) -> Vec<u64> {
         self.results
             .iter()
@@ -114,14 +231,171 @@ impl ProgressData {
             })
             .collect();
 
-        chars.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        // Break ties by character so the same history always gives the same set
+        chars.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap().then(a.0.cmp(&b.0)));
         chars.into_iter().take(count).collect()
     }
 }
 
+/// What records are kept apart by: results only compete with others typed
+/// under the same conditions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordKey {
+    pub mode: Mode,
+    pub length: PassageLength,
+    pub input_mode: InputMode,
+    /// Word list (`None` for older and imported results)
+    pub language: Option<String>,
+    /// Word tier (`None` for modes that don't draw from the word list, older
+    /// and imported results)
+    pub difficulty: Option<Difficulty>,
+}
+
+impl RecordKey {
+    /// Key a result counts towards, or `None` for custom passages and results
+    /// without a mode
+    pub fn of(result: &TestResult) -> Option<RecordKey> {
+        let mode = result.mode?;
+        Some(RecordKey {
+            mode,
+            length: result.length?,
+            input_mode: result.input_mode,
+            language: result.language.clone(),
+            difficulty: Self::difficulty(mode, result.word_options),
+        })
+    }
+
+    /// The word tier only changes the passage in modes that draw from the word list
+    pub fn difficulty(mode: Mode, options: Option<WordOptions>) -> Option<Difficulty> {
+        options.filter(|_| mode.uses_words()).map(|options| options.difficulty)
+    }
+
+    pub fn matches(&self, result: &TestResult) -> bool {
+        RecordKey::of(result).as_ref() == Some(self)
+    }
+
+    /// Conditions beyond mode and length, e.g. "Normal, english, Top 200"
+    pub fn details(&self) -> String {
+        let mut parts = vec![self.input_mode.name()];
+        parts.extend(self.language.as_deref());
+        parts.extend(self.difficulty.map(|d| d.name()));
+        parts.join(", ")
+    }
+}
+
+/// A result that beat the previous record for its key
+#[derive(Debug, Clone)]
+pub struct PersonalBest {
+    pub wpm: f64,
+    pub accuracy: f64,
+    pub timestamp: DateTime<Utc>,
+    pub previous_wpm: Option<f64>,
+}
+
+/// Keystrokes aimed at one character during a test
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyStats {
+    pub presses: usize,
+    /// Presses that followed another keystroke closely enough to be timed
+    pub timed: usize,
+    /// Total time since the previous keystroke over the timed presses
+    pub total_ms: u64,
+}
+
 #[derive(Debug, Clone)]
 pub struct CharStats {
     pub total_errors: usize,
     pub total_appearances: usize,
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn result(minute: u32, wpm: f64, input_mode: InputMode, language: Option<&str>) -> TestResult {
+        TestResult {
+            wpm,
+            accuracy: 96.0,
+            timestamp: Utc.with_ymd_and_hms(2026, 5, 1, 12, minute, 0).unwrap(),
+            duration_secs: 30,
+            mode: Some(Mode::Normal),
+            length: Some(PassageLength::Short),
+            input_mode,
+            language: language.map(str::to_string),
+            word_options: language.map(|_| WordOptions::default()),
+            ..Default::default()
+        }
+    }
+
+    fn key(input_mode: InputMode, language: Option<&str>) -> RecordKey {
+        RecordKey {
+            mode: Mode::Normal,
+            length: PassageLength::Short,
+            input_mode,
+            language: language.map(str::to_string),
+            difficulty: language.map(|_| Difficulty::default()),
+        }
+    }
+
+    #[test]
+    fn records_are_kept_apart_by_input_mode_and_word_list() {
+        let progress = ProgressData {
+            results: vec![
+                result(0, 50.0, InputMode::Normal, Some("english")),
+                result(1, 70.0, InputMode::Normal, Some("german")),
+                result(2, 40.0, InputMode::MustCorrect, Some("english")),
+                result(3, 90.0, InputMode::Normal, None),
+            ],
+        };
+        let best = |k: &RecordKey| progress.personal_best(k).map(|r| r.wpm);
+        assert_eq!(best(&key(InputMode::Normal, Some("english"))), Some(50.0));
+        assert_eq!(best(&key(InputMode::Normal, Some("german"))), Some(70.0));
+        assert_eq!(best(&key(InputMode::MustCorrect, Some("english"))), Some(40.0));
+        // Older results without a word list keep their own record
+        assert_eq!(best(&key(InputMode::Normal, None)), Some(90.0));
+        assert_eq!(best(&key(InputMode::StopOnWord, Some("english"))), None);
+
+        let mut harder = key(InputMode::Normal, Some("english"));
+        harder.difficulty = Some(Difficulty::Rare);
+        assert_eq!(best(&harder), None);
+        assert_eq!(progress.personal_bests().len(), 4);
+    }
+
+    #[test]
+    fn the_word_tier_only_splits_records_of_word_list_modes() {
+        let with_tier = |mode: Mode, difficulty: Difficulty| TestResult {
+            mode: Some(mode),
+            word_options: Some(WordOptions { difficulty, ..WordOptions::default() }),
+            ..result(0, 50.0, InputMode::Normal, Some("english"))
+        };
+        let drill = Mode::FingerDrill(crate::finger_map::Finger::LeftIndex);
+        let key = |result: TestResult| RecordKey::of(&result).unwrap();
+
+        assert_eq!(key(with_tier(drill, Difficulty::Rare)), key(with_tier(drill, Difficulty::Top200)));
+        assert_eq!(key(with_tier(drill, Difficulty::Rare)).difficulty, None);
+        assert_ne!(key(with_tier(Mode::Normal, Difficulty::Rare)), key(with_tier(Mode::Normal, Difficulty::Top200)));
+    }
+
+    #[test]
+    fn record_history_lists_each_improvement_in_order() {
+        let english = Some("english");
+        let progress = ProgressData {
+            // Out of order on purpose: history follows the timestamps
+            results: vec![
+                result(3, 55.0, InputMode::Normal, english),
+                result(0, 40.0, InputMode::Normal, english),
+                result(1, 38.0, InputMode::Normal, english),
+                result(2, 45.0, InputMode::Normal, english),
+                result(4, 80.0, InputMode::StopOnLetter, english),
+                result(5, 55.0, InputMode::Normal, english),
+            ],
+        };
+        let history = progress.personal_best_history(&key(InputMode::Normal, english));
+        let steps: Vec<(f64, Option<f64>)> = history.iter().map(|pb| (pb.wpm, pb.previous_wpm)).collect();
+        assert_eq!(steps, [(40.0, None), (45.0, Some(40.0)), (55.0, Some(45.0))]);
+        assert_eq!(history[2].timestamp, progress.results[0].timestamp);
+        assert!(progress.personal_best_history(&key(InputMode::MustCorrect, english)).is_empty());
+    }
+}
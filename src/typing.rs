@@ -0,0 +1,600 @@
+//! The typing engine: tracks what has been typed against a passage.
+//!
+//! Passages are normalized to NFC and split into extended grapheme clusters,
+//! so an accented letter, a Devanagari syllable or an emoji sequence is one
+//! position no matter how many code points it is made of. Keystrokes arrive
+//! one `char` at a time; when the grapheme under the cursor takes several of
+//! them (a letter followed by a combining accent, a ZWJ emoji sequence) they
+//! are collected until they either complete it or stop matching.
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::input_mode::InputMode;
+use crate::stats::KeyStats;
+
+const SPACE: &str = " ";
+/// Most extra letters kept after one word; further ones still count as errors
+pub const MAX_OVERFLOW: usize = 10;
+/// Gaps longer than this are pauses, not keystroke latency
+const PAUSE: Duration = Duration::from_secs(2);
+
+/// What was entered at one position of the passage
+#[derive(Debug, Clone, PartialEq)]
+pub enum Entry {
+    Typed(String),
+    /// Passed over with an early space
+    Skipped,
+}
+
+/// How a position of the passage should be displayed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphemeState {
+    Correct,
+    Incorrect,
+    Skipped,
+    Current,
+    Pending,
+}
+
+pub struct TypingSession {
+    target: Vec<String>,
+    entries: Vec<Entry>,
+    /// Code points typed so far toward a multi-code-point grapheme
+    composing: String,
+    input_mode: InputMode,
+    keystrokes: usize,
+    keystroke_errors: usize,
+    char_errors: HashMap<char, usize>,
+    key_stats: HashMap<char, KeyStats>,
+    last_keystroke: Option<Instant>,
+    /// Words in which a wrong key was pressed, by index
+    mistyped_words: HashSet<usize>,
+    /// Letters typed past the end of a word, by the position of the space
+    /// after it
+    overflow: HashMap<usize, Vec<String>>,
+}
+
+impl TypingSession {
+    pub fn new(text: &str, input_mode: InputMode) -> Self {
+        let normalized: String = text.nfc().collect();
+        TypingSession {
+            target: normalized.graphemes(true).map(str::to_string).collect(),
+            entries: Vec::new(),
+            composing: String::new(),
+            input_mode,
+            keystrokes: 0,
+            keystroke_errors: 0,
+            char_errors: HashMap::new(),
+            key_stats: HashMap::new(),
+            last_keystroke: None,
+            mistyped_words: HashSet::new(),
+            overflow: HashMap::new(),
+        }
+    }
+
+    pub fn set_input_mode(&mut self, input_mode: InputMode) {
+        self.input_mode = input_mode;
+    }
+
+    /// The passage, one grapheme per position
+    pub fn target(&self) -> &[String] {
+        &self.target
+    }
+
+    /// The whole passage as one string
+    pub fn text(&self) -> String {
+        self.target.concat()
+    }
+
+    /// Number of positions typed so far
+    pub fn position(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.composing.is_empty()
+    }
+
+    /// The grapheme under the cursor
+    pub fn current(&self) -> Option<&str> {
+        self.target.get(self.entries.len()).map(String::as_str)
+    }
+
+    pub fn char_errors(&self) -> &HashMap<char, usize> {
+        &self.char_errors
+    }
+
+    /// Presses and latency per target character
+    pub fn key_stats(&self) -> &HashMap<char, KeyStats> {
+        &self.key_stats
+    }
+
+    pub fn state(&self, index: usize) -> GraphemeState {
+        match self.entries.get(index) {
+            Some(Entry::Skipped) => GraphemeState::Skipped,
+            Some(_) if self.is_correct(index) => GraphemeState::Correct,
+            Some(_) => GraphemeState::Incorrect,
+            None if index == self.entries.len() => GraphemeState::Current,
+            None => GraphemeState::Pending,
+        }
+    }
+
+    /// Keystrokes that didn't match the passage, including ones a strict
+    /// input mode rejected
+    pub fn keystroke_errors(&self) -> usize {
+        self.keystroke_errors
+    }
+
+    /// What was typed at a position, unless it was skipped or not reached yet
+    pub fn typed(&self, index: usize) -> Option<&str> {
+        match self.entries.get(index)? {
+            Entry::Typed(typed) => Some(typed),
+            Entry::Skipped => None,
+        }
+    }
+
+    /// Extra letters typed after the word that ends before `index`
+    pub fn overflow(&self, index: usize) -> &[String] {
+        self.overflow.get(&index).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn type_char(&mut self, c: char) {
+        if !self.composing.is_empty() && !self.continues_composing(c) {
+            // The sequence stopped matching: it counts as one wrong grapheme
+            // and this key starts the next position
+            let attempt = std::mem::take(&mut self.composing);
+            self.commit(attempt);
+        }
+
+        let Some(target) = self.current().map(str::to_string) else {
+            return;
+        };
+        self.keystrokes += 1;
+        if let Some(ch) = target.chars().next() {
+            self.record_press(ch);
+        }
+
+        if c == ' ' && self.can_skip_word() {
+            // An early space skips the rest of the word
+            self.record_error(None);
+            self.skip_word();
+            return;
+        }
+        if c != ' ' && self.can_overflow() {
+            // A letter where the space should be runs past the end of the word.
+            // The press was recorded against the space above, so the error is too.
+            self.record_error(Some(' '));
+            let extra = self.overflow.entry(self.entries.len()).or_default();
+            if extra.len() < MAX_OVERFLOW {
+                extra.push(c.to_string());
+            }
+            return;
+        }
+
+        let mut candidate = std::mem::take(&mut self.composing);
+        candidate.push(c);
+        let candidate: String = candidate.nfc().collect();
+        if is_partial(&candidate, &target) {
+            // Part of a multi-code-point grapheme, wait for the rest
+            self.composing = candidate;
+        } else {
+            self.commit(candidate);
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        self.last_keystroke = Some(Instant::now());
+        if !self.composing.is_empty() {
+            self.composing.clear();
+        } else if self.overflow.get_mut(&self.entries.len()).and_then(Vec::pop).is_none() {
+            // Extra letters after the word go before the word itself
+            self.entries.pop();
+        }
+    }
+
+    /// Delete back to the start of the current word, or the previous word
+    /// (and the space after it) when the cursor is already at a word start
+    pub fn delete_word(&mut self) {
+        self.last_keystroke = Some(Instant::now());
+        self.composing.clear();
+        while self.entries.last().is_some_and(is_space) {
+            self.entries.pop();
+        }
+        while self.entries.last().is_some_and(|e| !is_space(e)) {
+            self.entries.pop();
+        }
+        let position = self.entries.len();
+        self.overflow.retain(|&index, _| index < position);
+    }
+
+    /// Every position has been typed
+    pub fn at_end(&self) -> bool {
+        self.entries.len() == self.target.len()
+    }
+
+    /// The passage has been typed and the input mode lets the test end
+    pub fn is_finished(&self) -> bool {
+        self.at_end() && !self.finish_blocked()
+    }
+
+    /// Strict modes hold the test open at the end of the passage until
+    /// the remaining errors are fixed
+    pub fn finish_blocked(&self) -> bool {
+        match self.input_mode {
+            InputMode::Normal | InputMode::StopOnLetter => false,
+            InputMode::StopOnWord => !self.current_word_correct(),
+            InputMode::MustCorrect => self.has_uncorrected_errors(),
+        }
+    }
+
+    /// Whether Enter may end the test before the end of the passage
+    pub fn can_finish_early(&self) -> bool {
+        self.input_mode != InputMode::MustCorrect || !self.has_uncorrected_errors()
+    }
+
+    pub fn has_uncorrected_errors(&self) -> bool {
+        self.overflow_count() > 0 || (0..self.entries.len()).any(|i| !self.is_correct(i))
+    }
+
+    pub fn correct_count(&self) -> usize {
+        (0..self.entries.len()).filter(|&i| self.is_correct(i)).count()
+    }
+
+    /// Character accuracy. Strict modes count every wrong keystroke, since the
+    /// passage itself always ends up correct.
+    pub fn accuracy(&self) -> f64 {
+        let (correct, total) = if self.input_mode.is_strict() {
+            (self.keystrokes - self.keystroke_errors, self.keystrokes)
+        } else {
+            (self.correct_count(), self.entries.len() + self.overflow_count())
+        };
+        if total > 0 {
+            correct as f64 / total as f64 * 100.0
+        } else {
+            100.0
+        }
+    }
+
+    /// Percentage of typed words that are correct. The word in progress only
+    /// counts once the test is over. In strict input modes a word also counts
+    /// as wrong if any wrong key was pressed while typing it.
+    pub fn word_accuracy(&self, test_over: bool) -> f64 {
+        let mut attempted = 0;
+        let mut correct = 0;
+        for (index, (start, end)) in self.word_ranges().into_iter().enumerate() {
+            let typed = self.entries.len().saturating_sub(start).min(end - start);
+            if typed == 0 || (typed < end - start && !test_over) {
+                break;
+            }
+            attempted += 1;
+            let clean = !self.input_mode.is_strict() || !self.mistyped_words.contains(&index);
+            let clean = clean && self.overflow(end).is_empty();
+            if clean && typed == end - start && (start..end).all(|i| self.is_correct(i)) {
+                correct += 1;
+            }
+        }
+        if attempted > 0 {
+            correct as f64 / attempted as f64 * 100.0
+        } else {
+            100.0
+        }
+    }
+
+    /// Extra letters typed past the ends of words, in all
+    pub fn overflow_count(&self) -> usize {
+        self.overflow.values().map(Vec::len).sum()
+    }
+
+    fn is_correct(&self, index: usize) -> bool {
+        matches!(&self.entries[index], Entry::Typed(typed) if *typed == self.target[index])
+    }
+
+    fn continues_composing(&self, c: char) -> bool {
+        let candidate: String = self.composing.chars().chain([c]).collect::<String>().nfc().collect();
+        self.current()
+            .is_some_and(|target| candidate == target || is_partial(&candidate, target))
+    }
+
+    /// Enter a grapheme at the cursor, if the input mode lets it advance
+    fn commit(&mut self, typed: String) {
+        let Some(target) = self.current().map(str::to_string) else {
+            return;
+        };
+        let correct = typed == target;
+        if !correct {
+            self.record_error(target.chars().next());
+        }
+        if self.accepts(&typed, &target, correct) {
+            self.entries.push(Entry::Typed(typed));
+        }
+    }
+
+    /// Count a keystroke aimed at `target_char`, timed from the previous one
+    fn record_press(&mut self, target_char: char) {
+        let now = Instant::now();
+        let stats = self.key_stats.entry(target_char).or_default();
+        stats.presses += 1;
+        if let Some(gap) = self.last_keystroke.map(|last| now - last)
+            && gap < PAUSE
+        {
+            stats.timed += 1;
+            stats.total_ms += gap.as_millis() as u64;
+        }
+        self.last_keystroke = Some(now);
+    }
+
+    fn record_error(&mut self, target_char: Option<char>) {
+        self.keystroke_errors += 1;
+        self.mistyped_words.insert(self.current_word_index());
+        if let Some(ch) = target_char {
+            *self.char_errors.entry(ch).or_insert(0) += 1;
+        }
+    }
+
+    /// Whether the input mode lets a keystroke advance the cursor
+    fn accepts(&self, candidate: &str, target: &str, correct: bool) -> bool {
+        match self.input_mode {
+            InputMode::Normal | InputMode::MustCorrect => true,
+            InputMode::StopOnLetter => correct,
+            InputMode::StopOnWord => target != SPACE || (candidate == SPACE && self.current_word_correct()),
+        }
+    }
+
+    /// A space can skip the rest of a word once it has been started
+    fn can_skip_word(&self) -> bool {
+        let pos = self.entries.len();
+        matches!(self.input_mode, InputMode::Normal | InputMode::MustCorrect)
+            && self.target.get(pos).is_some_and(|g| g != SPACE)
+            && pos > 0
+            && self.target[pos - 1] != SPACE
+    }
+
+    /// Letters typed at the end of a word pile up after it instead of
+    /// taking the place of the space. Modes that hold the cursor on a
+    /// mistake keep doing so.
+    fn can_overflow(&self) -> bool {
+        let pos = self.entries.len();
+        matches!(self.input_mode, InputMode::Normal | InputMode::MustCorrect)
+            && self.target.get(pos).is_some_and(|g| g == SPACE)
+            && pos > 0
+            && self.target[pos - 1] != SPACE
+    }
+
+    /// Mark the rest of the current word as missed and move past the space after it
+    fn skip_word(&mut self) {
+        while let Some(target) = self.target.get(self.entries.len()) {
+            if target == SPACE {
+                self.entries.push(Entry::Typed(SPACE.to_string()));
+                break;
+            }
+            self.entries.push(Entry::Skipped);
+        }
+    }
+
+    /// Index of the word under the cursor. A space belongs to the word before it.
+    fn current_word_index(&self) -> usize {
+        self.target[..self.entries.len()].iter().filter(|g| *g == SPACE).count()
+    }
+
+    /// Whether the word being typed matches the passage so far
+    fn current_word_correct(&self) -> bool {
+        let pos = self.entries.len();
+        let word_start = self.target[..pos].iter().rposition(|g| g == SPACE).map_or(0, |i| i + 1);
+        (word_start..pos).all(|i| self.is_correct(i))
+    }
+
+    /// Start and end positions of each word in the passage
+    fn word_ranges(&self) -> Vec<(usize, usize)> {
+        let mut ranges = Vec::new();
+        let mut start = 0;
+        for (i, g) in self.target.iter().enumerate() {
+            if g == SPACE {
+                if i > start {
+                    ranges.push((start, i));
+                }
+                start = i + 1;
+            }
+        }
+        if self.target.len() > start {
+            ranges.push((start, self.target.len()));
+        }
+        ranges
+    }
+}
+
+/// Whether `candidate` is the start of a grapheme that can't be typed as a
+/// single code point
+fn is_partial(candidate: &str, target: &str) -> bool {
+    if candidate == target || target.chars().count() < 2 {
+        return false;
+    }
+    let target: String = target.nfd().collect();
+    let candidate: String = candidate.nfd().collect();
+    target.starts_with(&candidate)
+}
+
+fn is_space(entry: &Entry) -> bool {
+    matches!(entry, Entry::Typed(typed) if typed == SPACE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_str(session: &mut TypingSession, text: &str) {
+        for c in text.chars() {
+            session.type_char(c);
+        }
+    }
+
+    #[test]
+    fn counts_graphemes_not_bytes() {
+        let session = TypingSession::new("zażółć gęślą jaźń", InputMode::Normal);
+        assert_eq!(session.target().len(), 17);
+
+        let session = TypingSession::new("नमस्ते", InputMode::Normal);
+        assert_eq!(session.target().len(), 3);
+
+        let session = TypingSession::new("👨‍👩‍👧 ok", InputMode::Normal);
+        assert_eq!(session.target().len(), 4);
+    }
+
+    #[test]
+    fn completes_accented_passage() {
+        let mut session = TypingSession::new("Grüße aus Köln", InputMode::Normal);
+        type_str(&mut session, "Grüße aus Köln");
+        assert!(session.is_finished());
+        assert_eq!(session.correct_count(), 14);
+        assert_eq!(session.accuracy(), 100.0);
+        assert!(session.char_errors().is_empty());
+    }
+
+    #[test]
+    fn decomposed_passage_matches_precomposed_input() {
+        // "café" with a combining acute accent
+        let mut session = TypingSession::new("cafe\u{301}", InputMode::Normal);
+        assert_eq!(session.target().len(), 4);
+        type_str(&mut session, "café");
+        assert!(session.is_finished());
+        assert_eq!(session.accuracy(), 100.0);
+    }
+
+    #[test]
+    fn composes_input_spread_over_several_chars() {
+        // e with a dot below and an acute accent has no precomposed form
+        let mut session = TypingSession::new("ẹ́", InputMode::Normal);
+        session.type_char('e');
+        session.type_char('\u{323}');
+        assert_eq!(session.position(), 0);
+        assert!(!session.is_empty());
+        session.type_char('\u{301}');
+        assert!(session.is_finished());
+        assert_eq!(session.accuracy(), 100.0);
+
+        let family = "👨\u{200d}👩\u{200d}👧";
+        let mut session = TypingSession::new(family, InputMode::Normal);
+        type_str(&mut session, family);
+        assert!(session.is_finished());
+        assert_eq!(session.correct_count(), 1);
+    }
+
+    #[test]
+    fn broken_composition_is_one_wrong_grapheme() {
+        let mut session = TypingSession::new("ẹ́x", InputMode::Normal);
+        type_str(&mut session, "ex");
+        assert!(session.is_finished());
+        assert_eq!(session.state(0), GraphemeState::Incorrect);
+        assert_eq!(session.state(1), GraphemeState::Correct);
+    }
+
+    #[test]
+    fn attributes_errors_to_the_target_grapheme() {
+        let mut session = TypingSession::new("żółw", InputMode::Normal);
+        type_str(&mut session, "zółw");
+        assert!(session.at_end());
+        assert_eq!(session.state(0), GraphemeState::Incorrect);
+        assert_eq!(session.state(1), GraphemeState::Correct);
+        assert_eq!(session.char_errors().get(&'ż'), Some(&1));
+        assert_eq!(session.accuracy(), 75.0);
+    }
+
+    #[test]
+    fn backspace_removes_whole_graphemes() {
+        let mut session = TypingSession::new("ñandú", InputMode::Normal);
+        type_str(&mut session, "ñandú");
+        session.backspace();
+        assert_eq!(session.position(), 4);
+        assert_eq!(session.current(), Some("ú"));
+
+        // A half-composed grapheme is discarded first
+        let mut session = TypingSession::new("ẹ́", InputMode::Normal);
+        session.type_char('e');
+        session.backspace();
+        assert!(session.is_empty());
+    }
+
+    #[test]
+    fn delete_word_handles_multibyte_words() {
+        let mut session = TypingSession::new("déjà vu ça", InputMode::Normal);
+        type_str(&mut session, "déjà vu ç");
+        session.delete_word();
+        assert_eq!(session.position(), 8);
+        session.delete_word();
+        assert_eq!(session.position(), 5);
+        session.delete_word();
+        assert_eq!(session.position(), 0);
+    }
+
+    #[test]
+    fn early_space_skips_rest_of_word() {
+        let mut session = TypingSession::new("straße öl", InputMode::Normal);
+        type_str(&mut session, "st öl");
+        assert!(session.is_finished());
+        assert_eq!(session.state(2), GraphemeState::Skipped);
+        assert_eq!(session.state(5), GraphemeState::Skipped);
+        assert_eq!(session.correct_count(), 5);
+        assert_eq!(session.word_accuracy(true), 50.0);
+    }
+
+    #[test]
+    fn letters_past_the_end_of_a_word_overflow() {
+        let mut session = TypingSession::new("cat sat", InputMode::Normal);
+        type_str(&mut session, "cats");
+        assert_eq!(session.position(), 3);
+        assert_eq!(session.overflow(3), ["s"]);
+        assert_eq!(session.accuracy(), 75.0);
+
+        // The extra letters are deleted before the word
+        session.backspace();
+        assert!(session.overflow(3).is_empty());
+        assert_eq!(session.position(), 3);
+
+        type_str(&mut session, "xy sat");
+        assert!(session.is_finished());
+        assert_eq!(session.overflow(3), ["x", "y"]);
+        assert_eq!(session.word_accuracy(true), 50.0);
+        assert_eq!(session.char_errors().get(&' '), Some(&3));
+        // Each extra letter was a press of the space key, as was the space that ended the word
+        assert_eq!(session.key_stats()[&' '].presses, 4);
+
+        session.delete_word();
+        session.delete_word();
+        assert!(session.overflow(3).is_empty());
+    }
+
+    #[test]
+    fn stop_on_letter_waits_for_the_accented_key() {
+        let mut session = TypingSession::new("où", InputMode::StopOnLetter);
+        type_str(&mut session, "ouù");
+        assert!(session.is_finished());
+        assert_eq!(session.accuracy(), 2.0 / 3.0 * 100.0);
+        assert_eq!(session.char_errors().get(&'ù'), Some(&1));
+    }
+
+    #[test]
+    fn must_correct_blocks_until_fixed() {
+        let mut session = TypingSession::new("żaba", InputMode::MustCorrect);
+        type_str(&mut session, "zaba");
+        assert!(session.at_end());
+        assert!(!session.is_finished());
+        assert!(!session.can_finish_early());
+        for _ in 0..4 {
+            session.backspace();
+        }
+        type_str(&mut session, "żaba");
+        assert!(session.is_finished());
+    }
+
+    #[test]
+    fn counts_presses_per_target_key() {
+        let mut session = TypingSession::new("ab", InputMode::StopOnLetter);
+        type_str(&mut session, "xab");
+        assert_eq!(session.key_stats()[&'a'].presses, 2);
+        assert_eq!(session.key_stats()[&'b'].presses, 1);
+        // The first keystroke has nothing to be timed from
+        assert_eq!(session.key_stats()[&'a'].timed, 1);
+    }
+}
@@ -0,0 +1,65 @@
+use chrono::Local;
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::achievements::{Achievement, AchievementData};
+use crate::ui::dialogs::centered_rect;
+use crate::ui::theme::{pending_char_style, subtitle_style, Theme};
+
+pub fn render_achievements(f: &mut Frame, achievements: &AchievementData, theme: &Theme) {
+    let area = centered_rect(70, 80, f.area());
+    f.render_widget(Clear, area);
+
+    let all = Achievement::all();
+    let unlocked = all.iter().filter(|a| achievements.unlocked_at(**a).is_some()).count();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD))
+        .title(format!(" 🏆 Achievements ({}/{}) ", unlocked, all.len()))
+        .title_style(Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD));
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .split(area);
+
+    let lines: Vec<Line> = all
+        .iter()
+        .map(|achievement| match achievements.unlocked_at(*achievement) {
+            Some(at) => Line::from(vec![
+                Span::styled("✓ ", Style::default().fg(theme.correct()).add_modifier(Modifier::BOLD)),
+                Span::styled(
+                    format!("{:<22}", achievement.name()),
+                    Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(format!("{:<44}", achievement.description()), subtitle_style(theme)),
+                Span::styled(
+                    at.with_timezone(&Local).format("%Y-%m-%d").to_string(),
+                    Style::default().fg(theme.secondary()),
+                ),
+            ]),
+            None => Line::from(vec![
+                Span::styled("· ", pending_char_style(theme)),
+                Span::styled(format!("{:<22}", achievement.name()), pending_char_style(theme)),
+                Span::styled(achievement.description(), pending_char_style(theme)),
+            ]),
+        })
+        .collect();
+
+    let controls = Line::from(vec![
+        Span::styled("[Esc] ", Style::default().fg(theme.subtitle())),
+        Span::raw("Close"),
+    ]);
+
+    f.render_widget(block, area);
+    f.render_widget(Paragraph::new(lines), chunks[0]);
+    f.render_widget(Paragraph::new(controls).alignment(Alignment::Center), chunks[1]);
+}
@@ -0,0 +1,125 @@
+//! The caret marking where the next keystroke goes.
+//!
+//! Most styles restyle the cell under the caret once the passage is drawn.
+//! The bar uses the terminal's own cursor, since a cell can't hold both a
+//! letter and a line in front of it. With the smooth caret on, the event
+//! loop ticks a glide from the old position to the new one instead of
+//! jumping there.
+
+use ratatui::buffer::Cell;
+use ratatui::style::{Modifier, Style};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::ui::theme::{current_char_style, Theme};
+
+/// How quickly the smooth caret closes in on the cursor: after this long
+/// it has covered about two thirds of the way
+const GLIDE: Duration = Duration::from_millis(40);
+/// Tick interval while the caret is gliding
+pub const FRAME: Duration = Duration::from_millis(16);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaretStyle {
+    /// The character on a filled background
+    #[default]
+    Block,
+    Underline,
+    /// A thin line before the character, using the terminal cursor
+    Bar,
+    /// The character itself in the caret color, with no fill behind it
+    Outline,
+    None,
+}
+
+impl CaretStyle {
+    pub fn next(&self) -> CaretStyle {
+        match self {
+            CaretStyle::Block => CaretStyle::Underline,
+            CaretStyle::Underline => CaretStyle::Bar,
+            CaretStyle::Bar => CaretStyle::Outline,
+            CaretStyle::Outline => CaretStyle::None,
+            CaretStyle::None => CaretStyle::Block,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CaretStyle::Block => "Block",
+            CaretStyle::Underline => "Underline",
+            CaretStyle::Bar => "Bar",
+            CaretStyle::Outline => "Outline",
+            CaretStyle::None => "None",
+        }
+    }
+
+    /// Restyle a cell under the caret. The bar and no caret leave it alone.
+    pub fn apply(&self, cell: &mut Cell, theme: &Theme) {
+        let style = match self {
+            CaretStyle::Block => current_char_style(theme),
+            CaretStyle::Underline => Style::default()
+                .underline_color(theme.current_bg())
+                .add_modifier(Modifier::UNDERLINED | Modifier::BOLD),
+            CaretStyle::Outline => Style::default().fg(theme.current_bg()).add_modifier(Modifier::BOLD),
+            CaretStyle::Bar | CaretStyle::None => return,
+        };
+        cell.set_style(style);
+    }
+}
+
+/// Where the smooth caret is drawn, trailing the real cursor position
+#[derive(Debug, Default)]
+pub struct SmoothCaret {
+    shown: f64,
+    last_tick: Option<Instant>,
+}
+
+impl SmoothCaret {
+    /// Move toward `target`, by how much depending on the time since the
+    /// last tick
+    pub fn tick(&mut self, target: usize, now: Instant) {
+        let elapsed = self.last_tick.map_or(Duration::ZERO, |last| now - last);
+        self.last_tick = Some(now);
+        let distance = target as f64 - self.shown;
+        if distance.abs() < 0.5 {
+            self.shown = target as f64;
+        } else {
+            let covered = 1.0 - (-elapsed.as_secs_f64() / GLIDE.as_secs_f64()).exp();
+            self.shown += distance * covered;
+        }
+    }
+
+    /// Passage position the caret is drawn at
+    pub fn position(&self) -> usize {
+        self.shown.round() as usize
+    }
+
+    /// Whether the caret still has some way to go to reach `target`
+    pub fn is_moving(&self, target: usize) -> bool {
+        self.shown != target as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smooth_caret_glides_to_the_cursor() {
+        let start = Instant::now();
+        let mut caret = SmoothCaret::default();
+        caret.tick(0, start);
+        caret.tick(10, start + FRAME);
+        assert!(caret.position() > 0 && caret.position() < 10);
+        assert!(caret.is_moving(10));
+
+        let mut now = start + FRAME;
+        for _ in 0..20 {
+            now += FRAME;
+            caret.tick(10, now);
+        }
+        assert_eq!(caret.position(), 10);
+        assert!(!caret.is_moving(10));
+    }
+}
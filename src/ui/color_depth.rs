@@ -0,0 +1,235 @@
+//! Fitting theme colors to what the terminal can show.
+//!
+//! Themes are defined in 24-bit color. On terminals without it, every cell
+//! of the finished frame is mapped to the nearest color in the 256- or
+//! 16-color palette, which also turns gradients into bands rather than
+//! dropping them. Monochrome replaces color with text attributes.
+
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier};
+use serde::{Deserialize, Serialize};
+use std::env;
+
+use crate::ui::theme::Theme;
+
+/// Channel levels of the 6x6x6 cube in the 256-color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+/// Spread between the brightest and dimmest channel below which a color
+/// counts as a shade of gray in 16-color mode
+const GRAY_CHROMA: u8 = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorDepth {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+    /// No color: bold, underline, dim and reverse instead
+    Monochrome,
+}
+
+impl ColorDepth {
+    pub fn from_name(name: &str) -> Option<ColorDepth> {
+        match name.to_ascii_lowercase().as_str() {
+            "truecolor" | "24bit" => Some(ColorDepth::TrueColor),
+            "256" => Some(ColorDepth::Ansi256),
+            "16" => Some(ColorDepth::Ansi16),
+            "mono" | "monochrome" | "none" => Some(ColorDepth::Monochrome),
+            _ => None,
+        }
+    }
+
+    /// Best guess from the environment
+    pub fn detect() -> ColorDepth {
+        let var = |name| env::var(name).ok();
+        detect_from(
+            var("COLORTERM").as_deref(),
+            var("TERM").as_deref(),
+            var("NO_COLOR").is_some_and(|v| !v.is_empty()),
+            var("WT_SESSION").is_some(),
+        )
+    }
+
+    /// Rewrite the colors of a rendered frame to fit this depth
+    pub fn apply(&self, buffer: &mut Buffer, theme: &Theme) {
+        if *self == ColorDepth::TrueColor {
+            return;
+        }
+        for cell in buffer.content.iter_mut() {
+            if *self == ColorDepth::Monochrome {
+                // Highlighted cells (the cursor, selections) are inverted; the
+                // rest keep their meaning through attributes
+                let cue = if cell.bg != Color::Reset {
+                    Modifier::REVERSED
+                } else if cell.fg == theme.error() {
+                    Modifier::BOLD | Modifier::UNDERLINED
+                } else if cell.fg == theme.pending() {
+                    Modifier::DIM
+                } else {
+                    Modifier::empty()
+                };
+                cell.modifier |= cue;
+                cell.fg = Color::Reset;
+                cell.bg = Color::Reset;
+                cell.underline_color = Color::Reset;
+            } else {
+                cell.fg = self.map(cell.fg);
+                cell.bg = self.map(cell.bg);
+                cell.underline_color = self.map(cell.underline_color);
+            }
+        }
+    }
+
+    fn map(&self, color: Color) -> Color {
+        let Color::Rgb(r, g, b) = color else {
+            return color;
+        };
+        match self {
+            ColorDepth::Ansi256 => to_256(r, g, b),
+            ColorDepth::Ansi16 => to_16(r, g, b),
+            ColorDepth::TrueColor | ColorDepth::Monochrome => color,
+        }
+    }
+}
+
+fn detect_from(colorterm: Option<&str>, term: Option<&str>, no_color: bool, windows_terminal: bool) -> ColorDepth {
+    // https://no-color.org
+    if no_color {
+        return ColorDepth::Monochrome;
+    }
+    if matches!(colorterm, Some("truecolor" | "24bit")) || windows_terminal {
+        return ColorDepth::TrueColor;
+    }
+    let Some(term) = term else {
+        return ColorDepth::Ansi16;
+    };
+    if term.contains("truecolor") || term.contains("24bit") || term.contains("direct") {
+        ColorDepth::TrueColor
+    } else if term.contains("256color") {
+        ColorDepth::Ansi256
+    } else if term == "dumb" || term.starts_with("vt1") || term.starts_with("vt2") {
+        // Serial consoles and hardware terminals
+        ColorDepth::Monochrome
+    } else {
+        ColorDepth::Ansi16
+    }
+}
+
+/// Nearest entry in the color cube or the gray ramp
+fn to_256(r: u8, g: u8, b: u8) -> Color {
+    let level = |c: u8| {
+        CUBE_LEVELS
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, level)| level.abs_diff(c))
+            .map_or(0, |(i, _)| i)
+    };
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+
+    // Grays run from 8 to 238 in steps of 10
+    let average = (r as u16 + g as u16 + b as u16) / 3;
+    let gray_index = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 8 + gray_index * 10;
+
+    if distance((r, g, b), (gray, gray, gray)) < distance((r, g, b), cube) {
+        Color::Indexed(232 + gray_index)
+    } else {
+        Color::Indexed(16 + 36 * ri as u8 + 6 * gi as u8 + bi as u8)
+    }
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x.abs_diff(y) as u32).pow(2);
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+/// Closest of the 16 basic colors by hue, so pastel theme colors keep
+/// their character instead of collapsing to gray. Each terminal has its
+/// own idea of what the 16 colors look like, so only hue and brightness
+/// are matched.
+fn to_16(r: u8, g: u8, b: u8) -> Color {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let bright = max >= 160;
+
+    if max - min < GRAY_CHROMA {
+        return match (r as u16 + g as u16 + b as u16) / 3 {
+            0..64 => Color::Black,
+            64..160 => Color::DarkGray,
+            160..224 => Color::Gray,
+            _ => Color::White,
+        };
+    }
+
+    let (r, g, b, chroma) = (r as f64, g as f64, b as f64, (max - min) as f64);
+    let hue = if max as f64 == r {
+        60.0 * ((g - b) / chroma).rem_euclid(6.0)
+    } else if max as f64 == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    };
+
+    // Orange has no slot of its own and reads as a warning, so it goes to yellow
+    match (hue as u16, bright) {
+        (20..70, true) => Color::LightYellow,
+        (20..70, false) => Color::Yellow,
+        (70..160, true) => Color::LightGreen,
+        (70..160, false) => Color::Green,
+        (160..200, true) => Color::LightCyan,
+        (160..200, false) => Color::Cyan,
+        (200..260, true) => Color::LightBlue,
+        (200..260, false) => Color::Blue,
+        (260..345, true) => Color::LightMagenta,
+        (260..345, false) => Color::Magenta,
+        (_, true) => Color::LightRed,
+        (_, false) => Color::Red,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+
+    #[test]
+    fn detects_depth_from_the_environment() {
+        assert_eq!(detect_from(Some("truecolor"), Some("xterm-256color"), false, false), ColorDepth::TrueColor);
+        assert_eq!(detect_from(None, Some("screen-256color"), false, false), ColorDepth::Ansi256);
+        assert_eq!(detect_from(None, Some("linux"), false, false), ColorDepth::Ansi16);
+        assert_eq!(detect_from(None, Some("vt220"), false, false), ColorDepth::Monochrome);
+        assert_eq!(detect_from(Some("truecolor"), None, true, false), ColorDepth::Monochrome);
+        assert_eq!(detect_from(None, None, false, true), ColorDepth::TrueColor);
+    }
+
+    #[test]
+    fn maps_to_the_nearest_palette_color() {
+        assert_eq!(to_256(255, 0, 0), Color::Indexed(196));
+        assert_eq!(to_256(128, 128, 128), Color::Indexed(244));
+        assert_eq!(to_256(251, 189, 46), Color::Indexed(214));
+
+        let theme = Theme::default();
+        let map = |color| ColorDepth::Ansi16.map(color);
+        assert_eq!(map(theme.error()), Color::LightRed);
+        assert_eq!(map(theme.warning()), Color::LightYellow);
+        assert_eq!(map(theme.pending()), Color::DarkGray);
+        assert_eq!(map(theme.current_fg()), Color::Black);
+        assert_eq!(map(Color::Rgb(136, 192, 208)), Color::LightCyan);
+    }
+
+    #[test]
+    fn monochrome_keeps_errors_and_the_cursor_visible() {
+        let theme = Theme::default();
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 3, 1));
+        buffer[(0, 0)].set_fg(theme.error());
+        buffer[(1, 0)].set_fg(theme.current_fg()).set_bg(theme.current_bg());
+        buffer[(2, 0)].set_fg(theme.correct());
+        ColorDepth::Monochrome.apply(&mut buffer, &theme);
+
+        assert_eq!(buffer[(0, 0)].modifier, Modifier::BOLD | Modifier::UNDERLINED);
+        assert_eq!(buffer[(1, 0)].modifier, Modifier::REVERSED);
+        assert_eq!(buffer[(2, 0)].modifier, Modifier::empty());
+        assert!(buffer.content.iter().all(|cell| cell.fg == Color::Reset && cell.bg == Color::Reset));
+    }
+}
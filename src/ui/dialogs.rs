@@ -55,8 +55,53 @@ pub fn render_confirmation_dialog(f: &mut Frame, title: &str, message: &str, the
     f.render_widget(controls_widget, chunks[1]);
 }
 
+/// Prompt for a passage seed or code, showing what has been typed so far
+pub fn render_seed_dialog(f: &mut Frame, input: &str, current: &str, theme: &Theme) {
+    let area = centered_rect(60, 30, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD))
+        .title(" Passage Seed ")
+        .title_style(Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD));
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([Constraint::Length(1), Constraint::Min(2), Constraint::Length(1)])
+        .split(area);
+
+    let field = Line::from(vec![
+        Span::styled("Seed: ", subtitle_style(theme)),
+        Span::styled(input, Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD)),
+        Span::styled("█", Style::default().fg(theme.secondary())),
+    ]);
+
+    let hint = Paragraph::new(format!(
+        "Current passage: {}\nA code sets the mode and word options too;\na plain number keeps the current ones.",
+        current
+    ))
+    .style(subtitle_style(theme))
+    .alignment(Alignment::Center)
+    .wrap(ratatui::widgets::Wrap { trim: false });
+
+    let controls = Line::from(vec![
+        Span::styled("[Enter] ", Style::default().fg(theme.correct()).add_modifier(Modifier::BOLD)),
+        Span::raw("Load  "),
+        Span::styled("[Esc] ", Style::default().fg(theme.subtitle())),
+        Span::raw("Cancel"),
+    ]);
+
+    f.render_widget(block, area);
+    f.render_widget(Paragraph::new(field).alignment(Alignment::Center), chunks[0]);
+    f.render_widget(hint, chunks[1]);
+    f.render_widget(Paragraph::new(controls).alignment(Alignment::Center), chunks[2]);
+}
+
 /// Helper function to create a centered rect using up certain percentage of the available rect
-fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
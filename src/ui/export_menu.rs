@@ -0,0 +1,332 @@
+use chrono::{Days, Local, NaiveDate};
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::export::{default_export_path, write_export, ExportFilter, ExportFormat};
+use crate::modes::Mode;
+use crate::stats::ProgressData;
+use crate::ui::dialogs::centered_rect;
+use crate::ui::theme::{subtitle_style, Theme};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportRange {
+    AllTime,
+    Today,
+    Last7Days,
+    Last30Days,
+}
+
+impl ExportRange {
+    pub fn next(&self) -> Self {
+        match self {
+            ExportRange::AllTime => ExportRange::Today,
+            ExportRange::Today => ExportRange::Last7Days,
+            ExportRange::Last7Days => ExportRange::Last30Days,
+            ExportRange::Last30Days => ExportRange::AllTime,
+        }
+    }
+
+    pub fn previous(&self) -> Self {
+        match self {
+            ExportRange::AllTime => ExportRange::Last30Days,
+            ExportRange::Today => ExportRange::AllTime,
+            ExportRange::Last7Days => ExportRange::Today,
+            ExportRange::Last30Days => ExportRange::Last7Days,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportRange::AllTime => "All time",
+            ExportRange::Today => "Today",
+            ExportRange::Last7Days => "Last 7 days",
+            ExportRange::Last30Days => "Last 30 days",
+        }
+    }
+
+    /// First day of the range, counted back from `today`
+    fn start(&self, today: NaiveDate) -> Option<NaiveDate> {
+        let days = match self {
+            ExportRange::AllTime => return None,
+            ExportRange::Today => 0,
+            ExportRange::Last7Days => 6,
+            ExportRange::Last30Days => 29,
+        };
+        today.checked_sub_days(Days::new(days))
+    }
+}
+
+const ROWS: usize = 5;
+const RANGE_ROW: usize = 1;
+const FROM_ROW: usize = 2;
+const TO_ROW: usize = 3;
+const DATE_FORMAT: &str = "%Y-%m-%d";
+/// Characters in a YYYY-MM-DD date
+const DATE_LENGTH: usize = 10;
+
+/// State for the in-app export dialog
+pub struct ExportMenu {
+    selected: usize,
+    format: ExportFormat,
+    /// First and last day to export, as typed. Empty leaves that end open.
+    from: String,
+    to: String,
+    mode: Option<Mode>,
+    status: Option<Result<String, String>>,
+}
+
+impl ExportMenu {
+    pub fn new() -> Self {
+        ExportMenu {
+            selected: 0,
+            format: ExportFormat::Csv,
+            from: String::new(),
+            to: String::new(),
+            mode: None,
+            status: None,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % ROWS;
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = (self.selected + ROWS - 1) % ROWS;
+    }
+
+    /// Change the value of the selected row. Dates move by a day.
+    pub fn cycle(&mut self, forward: bool) {
+        let today = Local::now().date_naive();
+        match self.selected {
+            0 => {
+                self.format = if forward {
+                    self.format.next()
+                } else {
+                    self.format.next().next()
+                }
+            }
+            RANGE_ROW => {
+                let range = self.range(today).unwrap_or(ExportRange::AllTime);
+                let range = if forward { range.next() } else { range.previous() };
+                self.from = range.start(today).map(|d| d.format(DATE_FORMAT).to_string()).unwrap_or_default();
+                self.to.clear();
+            }
+            FROM_ROW | TO_ROW => {
+                let field = if self.selected == FROM_ROW { &mut self.from } else { &mut self.to };
+                let date = NaiveDate::parse_from_str(field, DATE_FORMAT).ok().map_or(Some(today), |date| {
+                    if forward { date.succ_opt() } else { date.pred_opt() }
+                });
+                if let Some(date) = date {
+                    *field = date.format(DATE_FORMAT).to_string();
+                }
+            }
+            _ => {
+                // "All modes" followed by every mode
+                let mut options: Vec<Option<Mode>> = vec![None];
+                options.extend(Mode::all().into_iter().map(Some));
+                let idx = options.iter().position(|m| *m == self.mode).unwrap_or(0);
+                let len = options.len();
+                let next = if forward { (idx + 1) % len } else { (idx + len - 1) % len };
+                self.mode = options[next];
+            }
+        }
+        self.status = None;
+    }
+
+    /// Type into the selected date field
+    pub fn type_char(&mut self, c: char) {
+        if let Some(field) = self.date_field()
+            && (c.is_ascii_digit() || c == '-')
+            && field.len() < DATE_LENGTH
+        {
+            field.push(c);
+            self.status = None;
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if let Some(field) = self.date_field() {
+            field.pop();
+            self.status = None;
+        }
+    }
+
+    fn date_field(&mut self) -> Option<&mut String> {
+        match self.selected {
+            FROM_ROW => Some(&mut self.from),
+            TO_ROW => Some(&mut self.to),
+            _ => None,
+        }
+    }
+
+    /// The preset the dates match, if any
+    fn range(&self, today: NaiveDate) -> Option<ExportRange> {
+        if !self.to.is_empty() {
+            return None;
+        }
+        [ExportRange::AllTime, ExportRange::Today, ExportRange::Last7Days, ExportRange::Last30Days]
+            .into_iter()
+            .find(|range| range.start(today).map(|d| d.format(DATE_FORMAT).to_string()).unwrap_or_default() == self.from)
+    }
+
+    fn filter(&self) -> Result<ExportFilter, String> {
+        let date = |field: &str, name: &str| {
+            if field.is_empty() {
+                return Ok(None);
+            }
+            NaiveDate::parse_from_str(field, DATE_FORMAT)
+                .ok()
+                .filter(|_| field.len() == DATE_LENGTH)
+                .map(Some)
+                .ok_or_else(|| format!("Invalid {} date '{}' (expected YYYY-MM-DD)", name, field))
+        };
+        let from = date(&self.from, "start")?;
+        let to = date(&self.to, "end")?;
+        if let (Some(from), Some(to)) = (from, to)
+            && from > to
+        {
+            return Err("The start date is after the end date".to_string());
+        }
+        Ok(ExportFilter { from, to, mode: self.mode })
+    }
+
+    pub fn export(&mut self, progress: &ProgressData) {
+        let path = default_export_path(self.format);
+        self.status = Some(self.filter().and_then(|filter| {
+            write_export(progress, self.format, &filter, &path)
+                .map(|count| format!("Exported {} tests to {}", count, path.display()))
+        }));
+    }
+}
+
+pub fn render_export_menu(f: &mut Frame, menu: &ExportMenu, theme: &Theme) {
+    let area = centered_rect(60, 50, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD))
+        .title(" Export History ")
+        .title_style(Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD));
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([Constraint::Length(ROWS as u16 + 1), Constraint::Min(2), Constraint::Length(1)])
+        .split(area);
+
+    let mode_name = menu.mode.map(|m| m.name()).unwrap_or_else(|| "All modes".to_string());
+    let range = menu.range(Local::now().date_naive()).map_or("Custom", |range| range.name());
+    let date = |field: &str| if field.is_empty() { "Any".to_string() } else { field.to_string() };
+    let rows = [
+        ("Format", menu.format.name().to_string()),
+        ("Range", range.to_string()),
+        ("From", date(&menu.from)),
+        ("To", date(&menu.to)),
+        ("Mode", mode_name),
+    ];
+
+    let option_lines: Vec<Line> = rows
+        .iter()
+        .enumerate()
+        .map(|(i, (label, value))| {
+            let selected = i == menu.selected;
+            let marker = if selected { "▶ " } else { "  " };
+            let value_style = if selected {
+                Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.secondary())
+            };
+            Line::from(vec![
+                Span::styled(marker, Style::default().fg(theme.primary())),
+                Span::styled(format!("{:<8}", label), subtitle_style(theme)),
+                Span::styled(format!("◀ {} ▶", value), value_style),
+            ])
+        })
+        .collect();
+
+    let status = match &menu.status {
+        Some(Ok(msg)) => Line::from(Span::styled(msg.clone(), Style::default().fg(theme.correct()))),
+        Some(Err(msg)) => Line::from(Span::styled(msg.clone(), Style::default().fg(theme.error()))),
+        None if menu.selected == FROM_ROW || menu.selected == TO_ROW => Line::from(Span::styled(
+            "Type a date as YYYY-MM-DD, or leave it empty for no limit",
+            subtitle_style(theme),
+        )),
+        None => Line::from(Span::styled(
+            "Files are written to ~/.ratatap/exports/",
+            subtitle_style(theme),
+        )),
+    };
+
+    let controls = Line::from(vec![
+        Span::styled("↑↓ ", Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD)),
+        Span::raw("Select  "),
+        Span::styled("←→ ", Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD)),
+        Span::raw("Change  "),
+        Span::styled("[Enter] ", Style::default().fg(theme.correct()).add_modifier(Modifier::BOLD)),
+        Span::raw("Export  "),
+        Span::styled("[Esc] ", Style::default().fg(theme.subtitle())),
+        Span::raw("Close"),
+    ]);
+
+    f.render_widget(block, area);
+    f.render_widget(Paragraph::new(option_lines), chunks[0]);
+    f.render_widget(
+        Paragraph::new(status).wrap(Wrap { trim: false }).alignment(Alignment::Center),
+        chunks[1],
+    );
+    f.render_widget(Paragraph::new(controls).alignment(Alignment::Center), chunks[2]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typed(menu: &mut ExportMenu, row: usize, text: &str) {
+        menu.selected = row;
+        text.chars().for_each(|c| menu.type_char(c));
+    }
+
+    #[test]
+    fn typed_dates_bound_the_export() {
+        let mut menu = ExportMenu::new();
+        typed(&mut menu, FROM_ROW, "2026-02-05x");
+        typed(&mut menu, TO_ROW, "2026-02-250");
+        let filter = menu.filter().unwrap();
+        assert_eq!(filter.from, NaiveDate::from_ymd_opt(2026, 2, 5));
+        assert_eq!(filter.to, NaiveDate::from_ymd_opt(2026, 2, 25));
+
+        // Arrows move the selected date a day
+        menu.cycle(false);
+        assert_eq!(menu.to, "2026-02-24");
+
+        menu.backspace();
+        assert_eq!(menu.filter().err().unwrap(), "Invalid end date '2026-02-2' (expected YYYY-MM-DD)");
+        menu.to = "2026-01-31".to_string();
+        assert_eq!(menu.filter().err().unwrap(), "The start date is after the end date");
+    }
+
+    #[test]
+    fn presets_fill_in_the_start_date() {
+        let today = Local::now().date_naive();
+        let mut menu = ExportMenu::new();
+        assert_eq!(menu.range(today), Some(ExportRange::AllTime));
+
+        menu.selected = RANGE_ROW;
+        menu.cycle(true);
+        menu.cycle(true);
+        assert_eq!(menu.range(today), Some(ExportRange::Last7Days));
+        assert_eq!(menu.filter().unwrap().from, today.checked_sub_days(Days::new(6)));
+
+        // Editing a date by hand makes the range custom
+        typed(&mut menu, TO_ROW, "2026-01-01");
+        assert_eq!(menu.range(today), None);
+    }
+}
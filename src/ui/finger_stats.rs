@@ -0,0 +1,135 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::finger_stats::{FingerReport, KeyGroup};
+use crate::ui::dialogs::centered_rect;
+use crate::ui::theme::{subtitle_style, Theme};
+
+const BAR_WIDTH: usize = 20;
+
+/// Render the finger analytics overlay: an error-rate bar per finger (longer
+/// is weaker), hand and row totals, and the drill recommended for the weakest finger.
+pub fn render_finger_stats(f: &mut Frame, report: &FingerReport, theme: &Theme) {
+    let area = centered_rect(70, 80, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD))
+        .title(" ✋ Finger Stats ")
+        .title_style(Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD));
+
+    let header_style = subtitle_style(theme).add_modifier(Modifier::BOLD);
+    let weakest = report.weakest_finger();
+
+    // Bars are scaled to the highest error rate so the weak spots stand out
+    let worst_error_rate = report
+        .fingers
+        .iter()
+        .filter_map(|(_, group)| group.accuracy())
+        .map(|accuracy| 100.0 - accuracy)
+        .fold(0.0, f64::max);
+
+    let mut finger_lines = vec![Line::from(Span::styled(
+        format!("{:<13} {:<width$} {:>7} {:>8} {:>7}", "Finger", "Error rate", "Acc", "Latency", "Errors", width = BAR_WIDTH),
+        header_style,
+    ))];
+    for (finger, group) in &report.fingers {
+        let is_weakest = Some(*finger) == weakest;
+        let error_rate = group.accuracy().map_or(0.0, |accuracy| 100.0 - accuracy);
+        let filled = if worst_error_rate > 0.0 {
+            (error_rate / worst_error_rate * BAR_WIDTH as f64).round() as usize
+        } else {
+            0
+        };
+        let name_style = if is_weakest {
+            Style::default().fg(theme.error()).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.secondary())
+        };
+        let mut spans = vec![
+            Span::styled(format!("{:<13} ", finger.name()), name_style),
+            Span::styled("█".repeat(filled), Style::default().fg(theme.error())),
+            Span::styled("░".repeat(BAR_WIDTH - filled), subtitle_style(theme)),
+        ];
+        spans.extend(group_spans(group, theme));
+        if is_weakest {
+            spans.push(Span::styled("  ◀ weakest", Style::default().fg(theme.error())));
+        }
+        finger_lines.push(Line::from(spans));
+    }
+
+    let mut total_lines = vec![Line::from(Span::styled(
+        format!("{:<34} {:>7} {:>8} {:>7}", "Hands and rows", "Acc", "Latency", "Errors"),
+        header_style,
+    ))];
+    let hands = report.hands.iter().map(|(hand, group)| (hand.name(), group));
+    let rows = report.rows.iter().map(|(row, group)| (row.name(), group));
+    for (name, group) in hands.chain(rows) {
+        let mut spans = vec![Span::styled(format!("{:<34}", name), Style::default().fg(theme.secondary()))];
+        spans.extend(group_spans(group, theme));
+        total_lines.push(Line::from(spans));
+    }
+
+    let recommendation = match weakest {
+        Some(finger) => Line::from(vec![
+            Span::styled("Recommended: ", subtitle_style(theme)),
+            Span::styled(
+                format!("{} Drill", finger.name()),
+                Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" - press Enter to start it", subtitle_style(theme)),
+        ]),
+        None => Line::from(Span::styled(
+            "Finish a few tests to see which finger needs practice",
+            subtitle_style(theme),
+        )),
+    };
+
+    let controls = Line::from(vec![
+        Span::styled("[Enter] ", Style::default().fg(theme.correct()).add_modifier(Modifier::BOLD)),
+        Span::raw("Start drill  "),
+        Span::styled("[Esc] ", Style::default().fg(theme.subtitle())),
+        Span::raw("Close"),
+    ]);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([
+            Constraint::Length(finger_lines.len() as u16 + 1),
+            Constraint::Length(total_lines.len() as u16 + 1),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .split(area);
+
+    f.render_widget(block, area);
+    f.render_widget(Paragraph::new(finger_lines), chunks[0]);
+    f.render_widget(Paragraph::new(total_lines), chunks[1]);
+    f.render_widget(Paragraph::new(recommendation).alignment(Alignment::Center), chunks[2]);
+    f.render_widget(Paragraph::new(controls).alignment(Alignment::Center), chunks[3]);
+}
+
+/// Accuracy, average latency and error count columns, with `-` where nothing is recorded
+fn group_spans(group: &KeyGroup, theme: &Theme) -> Vec<Span<'static>> {
+    let accuracy = match group.accuracy() {
+        Some(accuracy) => format!(" {:>6.1}%", accuracy),
+        None => format!(" {:>7}", "-"),
+    };
+    let latency = match group.average_latency_ms() {
+        Some(ms) => format!(" {:>6.0}ms", ms),
+        None => format!(" {:>8}", "-"),
+    };
+    vec![
+        Span::styled(accuracy, Style::default().fg(theme.correct())),
+        Span::styled(latency, Style::default().fg(theme.primary())),
+        Span::styled(format!(" {:>7}", group.errors), Style::default().fg(theme.warning())),
+    ]
+}
@@ -1,67 +1,230 @@
 use ratatui::{
     layout::{Alignment, Rect},
-    style::Style,
+    style::{Color, Style},
     text::{Line, Span},
     widgets::{Block, BorderType, Borders, Paragraph},
     Frame,
 };
-use std::collections::HashMap;
 
-use crate::stats::CharStats;
-use crate::ui::theme::{current_key_style, key_error_color, subtitle_style, Theme};
+use crate::finger_map::{base_key, finger_for_key, needs_shift, Finger, Hand};
+use crate::heatmap::{Heatmap, HeatmapMode};
+use crate::ui::keyboard_layout::{KeyKind, KeyboardLayout, UNIT};
+use crate::ui::theme::{current_key_style, interpolate_color, subtitle_style, Theme};
 
-/// Get the style for a key based on whether it's current and its error history
-fn get_key_style(key: char, is_current: bool, char_stats: &HashMap<char, CharStats>, theme: &Theme) -> Style {
+/// Steps drawn in the legend's color scale
+const LEGEND_STEPS: usize = 5;
+
+/// Color for a key's position on the heatmap scale (0.0 coolest, 1.0 hottest)
+fn heat_color(theme: &Theme, mode: HeatmapMode, heat: f64) -> Color {
+    match mode {
+        // Frequency isn't good or bad, so it only shades toward the accent color
+        HeatmapMode::Frequency => interpolate_color(theme.pending(), theme.primary(), heat),
+        // Improvement is centred on "no change": greener is better, redder is worse
+        HeatmapMode::Improvement if heat < 0.5 => interpolate_color(theme.correct(), theme.pending(), heat * 2.0),
+        HeatmapMode::Improvement => interpolate_color(theme.pending(), theme.error(), (heat - 0.5) * 2.0),
+        HeatmapMode::ErrorRate | HeatmapMode::Latency if heat < 0.5 => {
+            interpolate_color(theme.correct(), theme.warning(), heat * 2.0)
+        }
+        HeatmapMode::ErrorRate | HeatmapMode::Latency => {
+            interpolate_color(theme.warning(), theme.error(), (heat - 0.5) * 2.0)
+        }
+    }
+}
+
+/// Tint for each finger, mirrored across the hands (both pinkies share a color)
+fn finger_color(theme: &Theme, finger: Finger) -> Color {
+    match finger {
+        Finger::LeftPinky | Finger::RightPinky => theme.error(),
+        Finger::LeftRing | Finger::RightRing => theme.warning(),
+        Finger::LeftMiddle | Finger::RightMiddle => theme.correct(),
+        Finger::LeftIndex | Finger::RightIndex => theme.primary(),
+    }
+}
+
+/// Get the style for a key: highlighted if it's next, otherwise tinted by
+/// finger with the overlay on, or by its place on the heatmap
+fn get_key_style(key: char, is_current: bool, heatmap: &Heatmap, finger_overlay: bool, theme: &Theme) -> Style {
     if is_current {
-        current_key_style(theme)
+        return current_key_style(theme);
+    }
+    if finger_overlay {
+        let color = finger_for_key(key).map_or(theme.secondary(), |finger| finger_color(theme, finger));
+        return Style::default().fg(color);
+    }
+    match heatmap.heat(key) {
+        Some(heat) => Style::default().fg(heat_color(theme, heatmap.mode, heat)),
+        None => Style::default().fg(theme.pending()),
+    }
+}
+
+/// Finger color key shown in place of the heatmap legend
+fn finger_legend(theme: &Theme) -> Line<'static> {
+    let mut spans = vec![Span::styled(" Fingers ", subtitle_style(theme))];
+    for (name, finger) in [
+        ("Pinky", Finger::LeftPinky),
+        ("Ring", Finger::LeftRing),
+        ("Middle", Finger::LeftMiddle),
+        ("Index", Finger::LeftIndex),
+    ] {
+        spans.push(Span::styled("■", Style::default().fg(finger_color(theme, finger))));
+        spans.push(Span::styled(format!(" {} ", name), subtitle_style(theme)));
+    }
+    Line::from(spans)
+}
+
+/// Which finger and hand strike the next key, and which Shift to hold
+fn next_key_hint(next: char, theme: &Theme) -> Line<'static> {
+    if next == ' ' {
+        return Line::from(Span::styled("Next: either thumb on Space", subtitle_style(theme)));
+    }
+    let Some(finger) = finger_for_key(next) else {
+        return Line::default();
+    };
+    let mut spans = vec![
+        Span::styled("Next: ", subtitle_style(theme)),
+        Span::styled(finger.name().to_string(), Style::default().fg(finger_color(theme, finger))),
+        Span::styled(format!(" ({})", finger.hand().name().to_lowercase()), subtitle_style(theme)),
+    ];
+    if needs_shift(next) {
+        let side = match finger.hand().other() {
+            Hand::Left => "Left",
+            Hand::Right => "Right",
+        };
+        spans.push(Span::styled(" + ", subtitle_style(theme)));
+        spans.push(Span::styled(format!("{} Shift", side), current_key_style(theme)));
+    }
+    Line::from(spans)
+}
+
+/// Heatmap name, scope and color scale, e.g. `Error rate · all time  0% ■■■■■ 12%`
+fn legend(heatmap: &Heatmap, theme: &Theme) -> Line<'static> {
+    let mut spans = vec![Span::styled(
+        format!(" {} · {} ", heatmap.mode.name(), heatmap.scope.name()),
+        subtitle_style(theme),
+    )];
+    if heatmap.is_empty() {
+        spans.push(Span::styled("(no data yet) ", subtitle_style(theme)));
     } else {
-        let error_count = char_stats.get(&key).map(|s| s.total_errors).unwrap_or(0);
-        Style::default().fg(key_error_color(theme, error_count))
+        spans.push(Span::styled(format!("{} ", heatmap.format(heatmap.low)), subtitle_style(theme)));
+        for step in 0..LEGEND_STEPS {
+            let heat = step as f64 / (LEGEND_STEPS - 1) as f64;
+            spans.push(Span::styled("■", Style::default().fg(heat_color(theme, heatmap.mode, heat))));
+        }
+        spans.push(Span::styled(format!(" {} ", heatmap.format(heatmap.high)), subtitle_style(theme)));
     }
+    Line::from(spans)
 }
 
+/// Cells per key unit at most, so the keyboard doesn't sprawl on wide terminals
+const MAX_UNIT_CELLS: u16 = 7;
+/// Key rows on every layout
+const ROWS: u16 = 5;
+
+/// Render the keyboard in the given physical layout, scaled to fit `area`:
+/// keys widen with the terminal and are drawn as boxes when there is room
+/// for three lines per row. With `finger_overlay` on, keys are tinted by the
+/// finger that types them and a hint names the finger (and Shift) for the
+/// next character; otherwise they show the heatmap.
 pub fn render_keyboard(
     f: &mut Frame,
     area: Rect,
     current_char: Option<char>,
-    char_stats: &HashMap<char, CharStats>,
+    heatmap: &Heatmap,
+    finger_overlay: bool,
+    layout: KeyboardLayout,
     theme: &Theme,
 ) {
-    let keys = [
-        vec!['`', '1', '2', '3', '4', '5', '6', '7', '8', '9', '0', '-', '='],
-        vec!['q', 'w', 'e', 'r', 't', 'y', 'u', 'i', 'o', 'p', '[', ']', '\\'],
-        vec!['a', 's', 'd', 'f', 'g', 'h', 'j', 'k', 'l', ';', '\''],
-        vec!['z', 'x', 'c', 'v', 'b', 'n', 'm', ',', '.', '/'],
-    ];
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.primary()))
+        .title(format!("Keyboard ({})", layout.name()))
+        .title_style(subtitle_style(theme))
+        .title_bottom(
+            if finger_overlay { finger_legend(theme) } else { legend(heatmap, theme) }.alignment(Alignment::Right),
+        );
+    let inner = block.inner(area);
 
-    let mut lines = Vec::new();
+    let hint = current_char.filter(|_| finger_overlay).map(|next| next_key_hint(next, theme));
+    let key_height = (inner.height.saturating_sub(hint.is_some() as u16) / ROWS).clamp(1, 3);
+
+    // Column of each quarter unit, so keys in every row share the same grid
+    let units = layout.width() / UNIT;
+    let cells = inner.width.min(units * MAX_UNIT_CELLS);
+    let column = |quarters: u16| (quarters as u32 * cells as u32 / layout.width() as u32) as u16;
+    let boxed = key_height == 3 && cells / units >= 4;
+
+    let current_key = current_char.map(base_key);
+    let shifted = current_char.is_some_and(needs_shift);
+    // Shift goes on the hand that isn't striking the key
+    let shift_hand = current_char
+        .filter(|&c| needs_shift(c))
+        .and_then(finger_for_key)
+        .map(|finger| finger.hand().other());
 
-    for row in &keys {
-        let spans: Vec<Span> = row
-            .iter()
-            .map(|&key| {
-                let is_current = current_char == Some(key);
-                let style = get_key_style(key, is_current, char_stats, theme);
-                Span::styled(format!(" {} ", key.to_uppercase()), style)
-            })
-            .collect();
-        lines.push(Line::from(spans));
+    let key_style = |kind: KeyKind| match kind {
+        KeyKind::Char(c) => get_key_style(c, current_key == Some(c), heatmap, finger_overlay, theme),
+        KeyKind::Space => get_key_style(' ', current_key == Some(' '), heatmap, finger_overlay, theme),
+        KeyKind::Shift(hand) if shift_hand == Some(hand) => current_key_style(theme),
+        // The pinkies reach the modifiers at the edges of the board
+        KeyKind::Shift(_) | KeyKind::Backspace | KeyKind::Tab | KeyKind::CapsLock | KeyKind::Enter | KeyKind::EnterTail
+            if finger_overlay =>
+        {
+            Style::default().fg(finger_color(theme, Finger::LeftPinky))
+        }
+        _ => Style::default().fg(theme.pending()),
+    };
+
+    let mut lines = Vec::new();
+    for row in layout.rows() {
+        let mut top = Vec::new();
+        let mut middle = Vec::new();
+        let mut bottom = Vec::new();
+        let mut start = 0;
+        for key in row {
+            let width = column(start + key.width) - column(start);
+            let style = key_style(key.kind);
+            if boxed {
+                let inside = width.saturating_sub(2) as usize;
+                let legend = key.legend(shifted, inside as u16);
+                let (top_edge, bottom_edge) = match key.kind {
+                    // The ISO Enter is an upside-down L whose lower half, a quarter
+                    // unit narrower, continues on the next row
+                    KeyKind::Enter if layout == KeyboardLayout::Iso => {
+                        let notch = (column(start + 1) - column(start)) as usize;
+                        let bottom_edge = match notch {
+                            0 => format!("│{}│", " ".repeat(inside)),
+                            _ => format!("╰{}╮{}│", "─".repeat(notch - 1), " ".repeat(inside.saturating_sub(notch))),
+                        };
+                        (format!("╭{}╮", "─".repeat(inside)), bottom_edge)
+                    }
+                    KeyKind::EnterTail => (format!("│{}│", " ".repeat(inside)), format!("╰{}╯", "─".repeat(inside))),
+                    _ => (format!("╭{}╮", "─".repeat(inside)), format!("╰{}╯", "─".repeat(inside))),
+                };
+                top.push(Span::styled(top_edge, style));
+                middle.push(Span::styled(format!("│{:^inside$}│", legend), style));
+                bottom.push(Span::styled(bottom_edge, style));
+            } else {
+                // Keep a cell of padding on either side
+                let legend = key.legend(shifted, width.saturating_sub(2));
+                middle.push(Span::styled(format!("{:^width$}", legend, width = width as usize), style));
+            }
+            start += key.width;
+        }
+        if boxed {
+            lines.extend([Line::from(top), Line::from(middle), Line::from(bottom)]);
+        } else {
+            lines.push(Line::from(middle));
+            if key_height > 1 {
+                lines.push(Line::default());
+            }
+        }
     }
 
-    // Space bar
-    let space_style = get_key_style(' ', current_char == Some(' '), char_stats, theme);
-    lines.push(Line::from(vec![Span::styled("     [ SPACE ]     ", space_style)]));
-
-    let keyboard = Paragraph::new(lines)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(theme.primary()))
-                .title("Keyboard")
-                .title_style(subtitle_style(theme)),
-        )
-        .alignment(Alignment::Center);
+    if let Some(hint) = hint {
+        lines.push(hint);
+    }
 
+    let keyboard = Paragraph::new(lines).block(block).alignment(Alignment::Center);
     f.render_widget(keyboard, area);
 }
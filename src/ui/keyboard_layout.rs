@@ -0,0 +1,204 @@
+//! Physical keyboard shapes for the on-screen keyboard.
+//!
+//! Widths are in quarter key units (a letter key is 4), so the usual
+//! 1.25u, 1.5u, 1.75u and 2.25u modifiers fit exactly. Every row of a
+//! shape adds up to the same width, which is what produces the stagger.
+
+use serde::{Deserialize, Serialize};
+
+use crate::finger_map::{shifted_key, Hand};
+
+/// One key unit, in quarters
+pub const UNIT: u16 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyboardLayout {
+    /// US-style: wide Enter on the home row, long left Shift
+    #[default]
+    Ansi,
+    /// European-style: tall Enter, short left Shift with an extra key beside it
+    Iso,
+    /// Straight columns with no stagger
+    Ortho,
+}
+
+/// What a key does, which decides its legend and highlighting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyKind {
+    /// A key that types a character (its unshifted one)
+    Char(char),
+    Shift(Hand),
+    Space,
+    Backspace,
+    Tab,
+    CapsLock,
+    Enter,
+    /// The lower half of an ISO Enter, drawn without a legend
+    EnterTail,
+    /// Ctrl, Alt and friends: drawn for shape, never highlighted
+    Other(&'static str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    pub kind: KeyKind,
+    /// Width in quarter units
+    pub width: u16,
+}
+
+impl Key {
+    /// Legend for a key with room for `room` characters, shortened when the
+    /// full name doesn't fit
+    pub fn legend(&self, shifted: bool, room: u16) -> String {
+        let (full, short) = match self.kind {
+            KeyKind::Char(c) if shifted => return shifted_key(c).to_string(),
+            KeyKind::Char(c) => return c.to_string(),
+            KeyKind::Shift(_) => ("Shift", "⇧"),
+            KeyKind::Space | KeyKind::EnterTail => ("", ""),
+            KeyKind::Backspace => ("Bksp", "⌫"),
+            KeyKind::Tab => ("Tab", "⇥"),
+            KeyKind::CapsLock => ("Caps", "⇪"),
+            KeyKind::Enter => ("Enter", "⏎"),
+            KeyKind::Other(name) => return name.chars().take(room as usize).collect(),
+        };
+        if full.chars().count() as u16 <= room {
+            full.to_string()
+        } else {
+            short.to_string()
+        }
+    }
+}
+
+impl KeyboardLayout {
+    pub fn next(&self) -> KeyboardLayout {
+        match self {
+            KeyboardLayout::Ansi => KeyboardLayout::Iso,
+            KeyboardLayout::Iso => KeyboardLayout::Ortho,
+            KeyboardLayout::Ortho => KeyboardLayout::Ansi,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeyboardLayout::Ansi => "ANSI",
+            KeyboardLayout::Iso => "ISO",
+            KeyboardLayout::Ortho => "Ortholinear",
+        }
+    }
+
+    /// Width of every row, in quarter units
+    pub fn width(&self) -> u16 {
+        match self {
+            KeyboardLayout::Ansi | KeyboardLayout::Iso => 15 * UNIT,
+            KeyboardLayout::Ortho => 14 * UNIT,
+        }
+    }
+
+    /// The five rows, number row first
+    pub fn rows(&self) -> Vec<Vec<Key>> {
+        let number_row = chars("`1234567890-=");
+        match self {
+            KeyboardLayout::Ansi => vec![
+                [number_row, vec![key(KeyKind::Backspace, 8)]].concat(),
+                [vec![key(KeyKind::Tab, 6)], chars("qwertyuiop[]"), vec![key(KeyKind::Char('\\'), 6)]].concat(),
+                [vec![key(KeyKind::CapsLock, 7)], chars("asdfghjkl;'"), vec![key(KeyKind::Enter, 9)]].concat(),
+                [
+                    vec![key(KeyKind::Shift(Hand::Left), 9)],
+                    chars("zxcvbnm,./"),
+                    vec![key(KeyKind::Shift(Hand::Right), 11)],
+                ]
+                .concat(),
+                bottom_row(),
+            ],
+            KeyboardLayout::Iso => vec![
+                [number_row, vec![key(KeyKind::Backspace, 8)]].concat(),
+                [vec![key(KeyKind::Tab, 6)], chars("qwertyuiop[]"), vec![key(KeyKind::Enter, 6)]].concat(),
+                [
+                    vec![key(KeyKind::CapsLock, 7)],
+                    chars("asdfghjkl;'\\"),
+                    vec![key(KeyKind::EnterTail, 5)],
+                ]
+                .concat(),
+                [
+                    vec![key(KeyKind::Shift(Hand::Left), 5), key(KeyKind::Other("<>"), 4)],
+                    chars("zxcvbnm,./"),
+                    vec![key(KeyKind::Shift(Hand::Right), 11)],
+                ]
+                .concat(),
+                bottom_row(),
+            ],
+            KeyboardLayout::Ortho => vec![
+                [number_row, vec![key(KeyKind::Backspace, 4)]].concat(),
+                [vec![key(KeyKind::Tab, 4)], chars("qwertyuiop[]\\")].concat(),
+                [vec![key(KeyKind::CapsLock, 4)], chars("asdfghjkl;'"), vec![key(KeyKind::Enter, 8)]].concat(),
+                [
+                    vec![key(KeyKind::Shift(Hand::Left), 8)],
+                    chars("zxcvbnm,./"),
+                    vec![key(KeyKind::Shift(Hand::Right), 8)],
+                ]
+                .concat(),
+                [
+                    ["Ctrl", "Alt", "Super"].map(|name| key(KeyKind::Other(name), 4)).to_vec(),
+                    vec![key(KeyKind::Space, 32)],
+                    ["Alt", "Super", "Ctrl"].map(|name| key(KeyKind::Other(name), 4)).to_vec(),
+                ]
+                .concat(),
+            ],
+        }
+    }
+}
+
+fn key(kind: KeyKind, width: u16) -> Key {
+    Key { kind, width }
+}
+
+fn chars(keys: &str) -> Vec<Key> {
+    keys.chars().map(|c| key(KeyKind::Char(c), UNIT)).collect()
+}
+
+/// Standard staggered-board modifier row
+fn bottom_row() -> Vec<Key> {
+    let side = |names: [&'static str; 3]| names.map(|name| key(KeyKind::Other(name), 5)).to_vec();
+    [
+        side(["Ctrl", "Super", "Alt"]),
+        vec![key(KeyKind::Space, 25)],
+        side(["Alt", "Super", "Menu"]),
+        vec![key(KeyKind::Other("Ctrl"), 5)],
+    ]
+    .concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_line_up_on_every_layout() {
+        for layout in [KeyboardLayout::Ansi, KeyboardLayout::Iso, KeyboardLayout::Ortho] {
+            for row in layout.rows() {
+                let width: u16 = row.iter().map(|k| k.width).sum();
+                assert_eq!(width, layout.width(), "{} row {:?}", layout.name(), row);
+            }
+        }
+    }
+
+    #[test]
+    fn every_typed_key_appears_once() {
+        for layout in [KeyboardLayout::Ansi, KeyboardLayout::Iso, KeyboardLayout::Ortho] {
+            let keys: Vec<char> = layout
+                .rows()
+                .concat()
+                .into_iter()
+                .filter_map(|k| match k.kind {
+                    KeyKind::Char(c) => Some(c),
+                    _ => None,
+                })
+                .collect();
+            let mut unique = keys.clone();
+            unique.sort();
+            unique.dedup();
+            assert_eq!((keys.len(), unique.len()), (47, 47), "{}", layout.name());
+        }
+    }
+}
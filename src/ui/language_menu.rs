@@ -0,0 +1,126 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::language::{self, Language};
+use crate::ui::dialogs::centered_rect;
+use crate::ui::theme::{subtitle_style, Theme};
+
+/// State for the in-app word list picker
+pub struct LanguageMenu {
+    names: Vec<String>,
+    selected: usize,
+    error: Option<String>,
+}
+
+impl LanguageMenu {
+    /// Open the picker with `current` highlighted
+    pub fn new(current: &str) -> Self {
+        let names = language::available();
+        let selected = names.iter().position(|n| n == current).unwrap_or(0);
+        LanguageMenu {
+            names,
+            selected,
+            error: None,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.names.len().max(1);
+        self.error = None;
+    }
+
+    pub fn select_previous(&mut self) {
+        let len = self.names.len().max(1);
+        self.selected = (self.selected + len - 1) % len;
+        self.error = None;
+    }
+
+    /// Load the highlighted list, keeping the menu open with an error if it fails
+    pub fn load_selected(&mut self) -> Option<Language> {
+        let name = self.names.get(self.selected)?;
+        match Language::load(name) {
+            Ok(language) => Some(language),
+            Err(msg) => {
+                self.error = Some(msg);
+                None
+            }
+        }
+    }
+}
+
+pub fn render_language_menu(f: &mut Frame, menu: &LanguageMenu, current: &str, theme: &Theme) {
+    let area = centered_rect(50, 70, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD))
+        .title(" Language ")
+        .title_style(Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD));
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([Constraint::Min(3), Constraint::Length(2), Constraint::Length(1)])
+        .split(area);
+
+    // Keep the selection in view when there are more lists than rows
+    let visible = chunks[0].height as usize;
+    let offset = menu.selected.saturating_sub(visible.saturating_sub(1));
+
+    let lines: Vec<Line> = menu
+        .names
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(visible)
+        .map(|(i, name)| {
+            let selected = i == menu.selected;
+            let marker = if selected { "▶ " } else { "  " };
+            let style = if selected {
+                Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.secondary())
+            };
+            let mut spans = vec![
+                Span::styled(marker, Style::default().fg(theme.primary())),
+                Span::styled(language::display_name(name), style),
+            ];
+            if name == current {
+                spans.push(Span::styled("  (current)", subtitle_style(theme)));
+            }
+            Line::from(spans)
+        })
+        .collect();
+
+    let status = match &menu.error {
+        Some(msg) => Line::from(Span::styled(msg.clone(), Style::default().fg(theme.error()))),
+        None => Line::from(Span::styled(
+            "Add your own lists to ~/.ratatap/languages/",
+            subtitle_style(theme),
+        )),
+    };
+
+    let controls = Line::from(vec![
+        Span::styled("↑↓ ", Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD)),
+        Span::raw("Select  "),
+        Span::styled("[Enter] ", Style::default().fg(theme.correct()).add_modifier(Modifier::BOLD)),
+        Span::raw("Use  "),
+        Span::styled("[Esc] ", Style::default().fg(theme.subtitle())),
+        Span::raw("Close"),
+    ]);
+
+    f.render_widget(block, area);
+    f.render_widget(Paragraph::new(lines), chunks[0]);
+    f.render_widget(
+        Paragraph::new(status).wrap(Wrap { trim: false }).alignment(Alignment::Center),
+        chunks[1],
+    );
+    f.render_widget(Paragraph::new(controls).alignment(Alignment::Center), chunks[2]);
+}
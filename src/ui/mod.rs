@@ -1,4 +1,16 @@
+pub mod achievements;
+pub mod caret;
 pub mod charts;
+pub mod color_depth;
 pub mod dialogs;
+pub mod export_menu;
+pub mod finger_stats;
 pub mod keyboard;
+pub mod keyboard_layout;
+pub mod language_menu;
+pub mod panels;
+pub mod passage;
+pub mod records;
 pub mod theme;
+pub mod toast;
+pub mod word_menu;
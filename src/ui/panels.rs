@@ -0,0 +1,268 @@
+//! Which panels are shown, and where, for the size of the terminal.
+//!
+//! The passage and the stats always get their rows. The other panels are
+//! added in order of importance while there is height left for them, and
+//! panels that sit side by side on a wide terminal are stacked, or dropped,
+//! on a narrow one. Whatever height is left over goes to the passage.
+
+use ratatui::layout::{Constraint, Layout, Rect};
+use serde::{Deserialize, Serialize};
+
+/// Rows of the logo box
+const LOGO_HEIGHT: u16 = 9;
+/// Columns the logo needs, borders included
+const LOGO_WIDTH: u16 = 56;
+/// Keyboard sizes from boxed keys down to one line per row
+const KEYBOARD_HEIGHTS: [u16; 3] = [18, 13, 8];
+/// Narrower than this the key legends no longer fit
+const KEYBOARD_MIN_WIDTH: u16 = 47;
+const STATS_HEIGHT: u16 = 5;
+/// Stats without the progress bar, on short terminals
+const SHORT_STATS_HEIGHT: u16 = 3;
+/// Below this many rows the stats panel shrinks
+const SHORT_HEIGHT: u16 = 25;
+const HISTORY_HEIGHT: u16 = 4;
+/// Width from which the sparkline fits beside the stats
+const SPARKLINE_MIN_WIDTH: u16 = 72;
+/// Width from which history and controls share a row
+const SIDE_BY_SIDE_WIDTH: u16 = 110;
+/// Width from which history joins the stats and the sparkline
+const THREE_COLUMNS_WIDTH: u16 = 150;
+/// Widest the passage gets in zen mode, for comfortable reading
+const ZEN_WIDTH: u16 = 90;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutPreset {
+    /// Logo, passage, keyboard and every panel that fits
+    #[default]
+    Full,
+    /// Everything but the logo
+    Compact,
+    /// The passage and a one-line WPM readout
+    Zen,
+}
+
+impl LayoutPreset {
+    pub fn next(&self) -> LayoutPreset {
+        match self {
+            LayoutPreset::Full => LayoutPreset::Compact,
+            LayoutPreset::Compact => LayoutPreset::Zen,
+            LayoutPreset::Zen => LayoutPreset::Full,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LayoutPreset::Full => "Full",
+            LayoutPreset::Compact => "Compact",
+            LayoutPreset::Zen => "Zen",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<LayoutPreset> {
+        match name.to_ascii_lowercase().as_str() {
+            "full" => Some(LayoutPreset::Full),
+            "compact" => Some(LayoutPreset::Compact),
+            "zen" => Some(LayoutPreset::Zen),
+            _ => None,
+        }
+    }
+}
+
+/// Panels that can be turned off in the full and compact layouts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Panels {
+    pub keyboard: bool,
+    pub sparkline: bool,
+    pub history: bool,
+    pub controls: bool,
+}
+
+impl Default for Panels {
+    fn default() -> Self {
+        Panels { keyboard: true, sparkline: true, history: true, controls: true }
+    }
+}
+
+/// Where each panel goes, or `None` when it isn't shown
+#[derive(Debug, Default, PartialEq)]
+pub struct Screen {
+    pub logo: Option<Rect>,
+    pub text: Rect,
+    pub keyboard: Option<Rect>,
+    pub stats: Option<Rect>,
+    pub sparkline: Option<Rect>,
+    pub history: Option<Rect>,
+    pub controls: Option<Rect>,
+    /// The one-line readout under the passage in zen mode
+    pub live_wpm: Option<Rect>,
+}
+
+/// Lay out `area`. `text_height` is the rows the passage needs without a
+/// border and `controls_height` the rows the controls panel needs at a
+/// given width, border included.
+pub fn plan(
+    area: Rect,
+    preset: LayoutPreset,
+    panels: Panels,
+    text_height: u16,
+    controls_height: impl Fn(u16) -> u16,
+) -> Screen {
+    if preset == LayoutPreset::Zen {
+        return zen(area, text_height);
+    }
+
+    let text_height = text_height + 2;
+    let stats_height = if area.height < SHORT_HEIGHT { SHORT_STATS_HEIGHT } else { STATS_HEIGHT };
+    let mut free = area.height.saturating_sub(text_height + stats_height);
+    let mut take = |height: u16| {
+        let fits = height <= free;
+        if fits {
+            free -= height;
+        }
+        fits
+    };
+
+    // Panels below the stats, as (height, history, controls) rows
+    let history_beside_stats = panels.history && area.width >= THREE_COLUMNS_WIDTH;
+    let history_below = panels.history && !history_beside_stats;
+    let mut rows: Vec<(u16, bool, bool)> = Vec::new();
+    if history_below && panels.controls && area.width >= SIDE_BY_SIDE_WIDTH {
+        let height = HISTORY_HEIGHT.max(controls_height(area.width / 2));
+        if take(height) {
+            rows.push((height, true, true));
+        } else if take(HISTORY_HEIGHT) {
+            rows.push((HISTORY_HEIGHT, true, false));
+        }
+    } else {
+        if history_below && take(HISTORY_HEIGHT) {
+            rows.push((HISTORY_HEIGHT, true, false));
+        }
+        let height = controls_height(area.width);
+        if panels.controls && take(height) {
+            rows.push((height, false, true));
+        }
+    }
+
+    let logo = preset == LayoutPreset::Full && area.width >= LOGO_WIDTH && take(LOGO_HEIGHT);
+    let keyboard_height = if panels.keyboard && area.width >= KEYBOARD_MIN_WIDTH {
+        KEYBOARD_HEIGHTS.into_iter().find(|&height| take(height))
+    } else {
+        None
+    };
+
+    let mut constraints = Vec::new();
+    if logo {
+        constraints.push(Constraint::Length(LOGO_HEIGHT));
+    }
+    constraints.push(Constraint::Min(text_height));
+    if let Some(height) = keyboard_height {
+        constraints.push(Constraint::Length(height));
+    }
+    constraints.push(Constraint::Length(stats_height));
+    constraints.extend(rows.iter().map(|&(height, ..)| Constraint::Length(height)));
+    let chunks = Layout::vertical(constraints).split(area);
+    let mut chunks = chunks.iter().copied();
+
+    let mut screen = Screen::default();
+    if logo {
+        screen.logo = chunks.next();
+    }
+    screen.text = chunks.next().unwrap_or_default();
+    if keyboard_height.is_some() {
+        screen.keyboard = chunks.next();
+    }
+
+    let stats_row = chunks.next().unwrap_or_default();
+    let sparkline = panels.sparkline && area.width >= SPARKLINE_MIN_WIDTH;
+    let columns = 1 + sparkline as u32 + history_beside_stats as u32;
+    let stats_columns = Layout::horizontal(vec![Constraint::Ratio(1, columns); columns as usize]).split(stats_row);
+    screen.stats = Some(stats_columns[0]);
+    if sparkline {
+        screen.sparkline = Some(stats_columns[1]);
+    }
+    if history_beside_stats {
+        screen.history = stats_columns.last().copied();
+    }
+
+    for (row, (_, history, controls)) in chunks.zip(rows) {
+        match (history, controls) {
+            (true, true) => {
+                let halves = Layout::horizontal([Constraint::Percentage(50); 2]).split(row);
+                screen.history = Some(halves[0]);
+                screen.controls = Some(halves[1]);
+            }
+            (true, false) => screen.history = Some(row),
+            _ => screen.controls = Some(row),
+        }
+    }
+    screen
+}
+
+/// The passage in the middle of the screen with the WPM readout under it
+fn zen(area: Rect, text_height: u16) -> Screen {
+    let [_, text, live_wpm, _] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(text_height),
+        Constraint::Length(1),
+        Constraint::Fill(1),
+    ])
+    .areas(area);
+    let width = area.width.min(ZEN_WIDTH);
+    let center = |row: Rect| Rect { x: area.x + (area.width - width) / 2, width, ..row };
+    Screen { text: center(text), live_wpm: Some(center(live_wpm)), ..Screen::default() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(width: u16, height: u16, preset: LayoutPreset, panels: Panels) -> Screen {
+        // Controls that take 400 columns of text
+        let controls = |width: u16| 400_u16.div_ceil(width.saturating_sub(2).max(1)) + 2;
+        plan(Rect::new(0, 0, width, height), preset, panels, 3, controls)
+    }
+
+    #[test]
+    fn full_layout_fits_everything_on_a_large_terminal() {
+        let screen = screen(160, 60, LayoutPreset::Full, Panels::default());
+        assert_eq!(screen.logo.map(|r| r.height), Some(LOGO_HEIGHT));
+        assert_eq!(screen.keyboard.map(|r| r.height), Some(18));
+        // History joins the stats row; the controls get a row of their own
+        assert_eq!(screen.history.map(|r| r.y), screen.stats.map(|r| r.y));
+        assert_eq!(screen.controls.map(|r| r.width), Some(160));
+        assert!(screen.text.height >= 5);
+    }
+
+    #[test]
+    fn small_panes_drop_the_logo_and_keyboard_first() {
+        let screen = screen(80, 24, LayoutPreset::Full, Panels::default());
+        assert_eq!(screen.logo, None);
+        assert_eq!(screen.keyboard, None);
+        assert!(screen.history.is_some() && screen.controls.is_some());
+        assert!(screen.text.height >= 5);
+
+        // Too narrow for the sparkline beside the stats
+        let screen = super::tests::screen(60, 40, LayoutPreset::Compact, Panels::default());
+        assert_eq!(screen.sparkline, None);
+        assert_eq!(screen.stats.map(|r| r.width), Some(60));
+    }
+
+    #[test]
+    fn hidden_panels_give_their_room_to_the_rest() {
+        let panels = Panels { history: false, controls: false, ..Panels::default() };
+        let screen = screen(100, 30, LayoutPreset::Compact, panels);
+        assert_eq!((screen.history, screen.controls, screen.logo), (None, None, None));
+        assert_eq!(screen.keyboard.map(|r| r.height), Some(18));
+    }
+
+    #[test]
+    fn zen_centers_the_passage() {
+        let screen = screen(120, 30, LayoutPreset::Zen, Panels::default());
+        assert_eq!(screen.text, Rect::new(15, 13, 90, 3));
+        assert_eq!(screen.live_wpm, Some(Rect::new(15, 16, 90, 1)));
+        assert_eq!((screen.stats, screen.keyboard), (None, None));
+    }
+}
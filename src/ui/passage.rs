@@ -0,0 +1,379 @@
+//! Laying out and drawing the passage being typed.
+//!
+//! The passage is word-wrapped once per passage and panel width, and only
+//! the few lines around the cursor are styled and drawn each frame, so even
+//! a whole book from `--file` costs no more to draw than a short passage.
+//! Letters typed past the end of a word are drawn after it, pushing the
+//! rest of the line along.
+
+use ratatui::{
+    layout::{Position, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+    Frame,
+};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::ops::Range;
+
+use crate::typing::{GraphemeState, TypingSession};
+use crate::ui::caret::CaretStyle;
+use crate::ui::theme::{correct_char_style, incorrect_char_style, pending_char_style, Theme};
+
+const SPACE: &str = " ";
+/// Stands in for a space typed where a letter was expected
+const TYPED_SPACE: &str = "·";
+/// Most passage lines shown at once: the one before the cursor's, the
+/// cursor's and the one after
+const VISIBLE_LINES: usize = 3;
+
+/// How mistakes are marked on top of the error color, for anyone who can't
+/// rely on telling the colors apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCue {
+    #[default]
+    ColorOnly,
+    Underline,
+    Strikethrough,
+    /// The character actually typed, on a line above the passage
+    TypedAbove,
+    /// The character actually typed, on a line below the passage
+    TypedBelow,
+}
+
+impl ErrorCue {
+    pub fn next(&self) -> ErrorCue {
+        match self {
+            ErrorCue::ColorOnly => ErrorCue::Underline,
+            ErrorCue::Underline => ErrorCue::Strikethrough,
+            ErrorCue::Strikethrough => ErrorCue::TypedAbove,
+            ErrorCue::TypedAbove => ErrorCue::TypedBelow,
+            ErrorCue::TypedBelow => ErrorCue::ColorOnly,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ErrorCue::ColorOnly => "Color only",
+            ErrorCue::Underline => "Underline",
+            ErrorCue::Strikethrough => "Strikethrough",
+            ErrorCue::TypedAbove => "Typed above",
+            ErrorCue::TypedBelow => "Typed below",
+        }
+    }
+
+    fn modifier(&self) -> Modifier {
+        match self {
+            ErrorCue::Underline => Modifier::UNDERLINED,
+            ErrorCue::Strikethrough => Modifier::CROSSED_OUT,
+            ErrorCue::ColorOnly | ErrorCue::TypedAbove | ErrorCue::TypedBelow => Modifier::empty(),
+        }
+    }
+
+    fn shows_typed(&self) -> bool {
+        matches!(self, ErrorCue::TypedAbove | ErrorCue::TypedBelow)
+    }
+
+    /// Screen rows per line of the passage
+    fn rows_per_line(&self) -> usize {
+        if self.shows_typed() { 2 } else { 1 }
+    }
+}
+
+/// Rows the passage needs to show all the lines it can, not counting a border
+pub fn passage_height(cue: ErrorCue) -> u16 {
+    (VISIBLE_LINES * cue.rows_per_line()) as u16
+}
+
+/// How the passage is marked up besides the colors of the theme
+pub struct Markup<'a> {
+    pub cue: ErrorCue,
+    pub caret: CaretStyle,
+    /// Where the caret is drawn, which trails the cursor while the smooth
+    /// caret glides
+    pub caret_position: usize,
+    pub theme: &'a Theme,
+}
+
+fn width(grapheme: &str) -> usize {
+    Span::raw(grapheme).width()
+}
+
+/// Columns taken by the extra letters typed before position `index`
+fn overflow_width(session: &TypingSession, index: usize) -> usize {
+    session.overflow(index).iter().map(|g| width(g)).sum()
+}
+
+/// Split the passage into lines at most `width` columns wide, breaking
+/// after spaces. A word too long for a line of its own is broken wherever
+/// it runs out of room. `extra` gives the columns of overflow drawn before
+/// a position. Returns the graphemes on each line.
+fn wrap(graphemes: &[String], width: u16, extra: impl Fn(usize) -> usize) -> Vec<Range<usize>> {
+    let width = width.max(1) as usize;
+    let columns = |range: Range<usize>| range.map(|i| self::width(&graphemes[i]) + extra(i)).sum::<usize>();
+
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut used = 0;
+    let mut i = 0;
+    while i < graphemes.len() {
+        // A word and the spaces after it
+        let mut end = i;
+        while end < graphemes.len() && graphemes[end] != SPACE {
+            end += 1;
+        }
+        while end < graphemes.len() && graphemes[end] == SPACE {
+            end += 1;
+        }
+
+        let token = columns(i..end);
+        if used + token > width && used > 0 {
+            lines.push(start..i);
+            start = i;
+            used = 0;
+        }
+        if token > width {
+            for j in i..end {
+                let grapheme = columns(j..j + 1);
+                if used + grapheme > width && used > 0 {
+                    lines.push(start..j);
+                    start = j;
+                    used = 0;
+                }
+                used += grapheme;
+            }
+        } else {
+            used += token;
+        }
+        i = end;
+    }
+    if start < graphemes.len() || lines.is_empty() {
+        lines.push(start..graphemes.len());
+    }
+    lines
+}
+
+/// Where the passage breaks into lines, kept between frames. Reset it with
+/// `PassageLayout::default()` whenever a new passage starts.
+#[derive(Debug, Default)]
+pub struct PassageLayout {
+    width: u16,
+    /// Extra letters typed when the lines were worked out
+    overflow: usize,
+    lines: Vec<Range<usize>>,
+}
+
+impl PassageLayout {
+    /// Line breaks for `width`, wrapping again only when the width or the
+    /// number of extra letters changed
+    fn lines(&mut self, session: &TypingSession, width: u16) -> &[Range<usize>] {
+        let overflow = session.overflow_count();
+        if self.lines.is_empty() || self.width != width || self.overflow != overflow {
+            self.width = width;
+            self.overflow = overflow;
+            self.lines = wrap(session.target(), width, |i| overflow_width(session, i));
+        }
+        &self.lines
+    }
+}
+
+/// Draw the passage in `block`, scrolled so the line being typed is in view
+/// with the line before it for context. Returns the cell under the caret,
+/// for placing the terminal cursor.
+pub fn render_passage(
+    f: &mut Frame,
+    area: Rect,
+    block: Block,
+    session: &TypingSession,
+    layout: &RefCell<PassageLayout>,
+    markup: &Markup,
+) -> Option<Position> {
+    let Markup { cue, theme, .. } = *markup;
+    let inner = block.inner(area);
+    let mut layout = layout.borrow_mut();
+    let lines = layout.lines(session, inner.width);
+
+    let rows_per_line = cue.rows_per_line();
+    let visible = (inner.height as usize / rows_per_line).clamp(1, VISIBLE_LINES);
+    let current = cursor_line(lines, session.position());
+    let shown = viewport(current, lines.len(), visible);
+
+    let mut block = block;
+    if lines.len() > visible {
+        block = block.title_bottom(
+            Line::from(format!(" Line {} of {} ", current + 1, lines.len())).right_aligned(),
+        );
+    }
+    let text = passage_lines(session, &lines[shown.clone()], cue, theme);
+    f.render_widget(Paragraph::new(text).block(block), area);
+
+    // The smooth caret only glides along a line and jumps between them
+    let mut position = markup.caret_position;
+    if cursor_line(lines, position) != current {
+        position = session.position();
+    }
+    let grapheme = session.target().get(position)?;
+    let row = (current - shown.start) * rows_per_line + usize::from(cue == ErrorCue::TypedAbove);
+    let column: usize = (lines[current].start..position)
+        .map(|i| width(&session.target()[i]) + overflow_width(session, i))
+        .sum::<usize>()
+        + overflow_width(session, position);
+    let caret = Position::new(inner.x + column as u16, inner.y + row as u16);
+    if !inner.contains(caret) {
+        return None;
+    }
+    for x in caret.x..(caret.x + width(grapheme).max(1) as u16).min(inner.right()) {
+        markup.caret.apply(&mut f.buffer_mut()[(x, caret.y)], theme);
+    }
+    Some(caret)
+}
+
+/// Index of the line holding `position`; the end of the passage counts as
+/// part of the last line
+fn cursor_line(lines: &[Range<usize>], position: usize) -> usize {
+    lines
+        .partition_point(|line| line.end <= position)
+        .min(lines.len().saturating_sub(1))
+}
+
+/// Lines to show: one line of context above the cursor's, never scrolling
+/// past the end of the passage
+fn viewport(current: usize, total: usize, visible: usize) -> Range<usize> {
+    let first = current.saturating_sub(1).min(total.saturating_sub(visible));
+    first..(first + visible).min(total)
+}
+
+/// `lines` of the passage, styled by typing state. With a typed-character
+/// cue every passage line gets a companion line, so the text doesn't shift
+/// when the first mistake appears.
+fn passage_lines(session: &TypingSession, lines: &[Range<usize>], cue: ErrorCue, theme: &Theme) -> Vec<Line<'static>> {
+    let target = session.target();
+    let mut styled = Vec::new();
+    for range in lines.iter().cloned() {
+        let mut text = Vec::new();
+        let mut typed = Vec::new();
+        for i in range {
+            let state = session.state(i);
+            for extra in session.overflow(i) {
+                text.push(Span::styled(extra.clone(), overflow_style(cue, theme)));
+                if cue.shows_typed() {
+                    typed.push(Span::raw(" ".repeat(width(extra))));
+                }
+            }
+            text.push(Span::styled(target[i].clone(), grapheme_style(state, cue, theme)));
+            if cue.shows_typed() {
+                typed.push(typed_span(session, i, theme));
+            }
+        }
+        match cue {
+            ErrorCue::TypedAbove => styled.extend([Line::from(typed), Line::from(text)]),
+            ErrorCue::TypedBelow => styled.extend([Line::from(text), Line::from(typed)]),
+            _ => styled.push(Line::from(text)),
+        }
+    }
+    styled
+}
+
+fn grapheme_style(state: GraphemeState, cue: ErrorCue, theme: &Theme) -> Style {
+    match state {
+        GraphemeState::Correct => correct_char_style(theme),
+        GraphemeState::Incorrect => incorrect_char_style(theme).add_modifier(cue.modifier()),
+        GraphemeState::Skipped => incorrect_char_style(theme).add_modifier(Modifier::DIM | cue.modifier()),
+        // The caret is drawn over the current character afterwards
+        GraphemeState::Current | GraphemeState::Pending => pending_char_style(theme),
+    }
+}
+
+/// Letters typed past the end of a word
+fn overflow_style(cue: ErrorCue, theme: &Theme) -> Style {
+    incorrect_char_style(theme).add_modifier(Modifier::ITALIC | cue.modifier())
+}
+
+/// The wrong character typed at `index`, or blanks of the same width
+fn typed_span(session: &TypingSession, index: usize, theme: &Theme) -> Span<'static> {
+    let width = width(&session.target()[index]);
+    let typed = match session.typed(index) {
+        Some(typed) if session.state(index) == GraphemeState::Incorrect => typed,
+        _ => return Span::raw(" ".repeat(width)),
+    };
+    let shown = if typed == SPACE { TYPED_SPACE } else { typed };
+    // Anything wider or narrower than the expected character would push
+    // the rest of the line out of step
+    let shown = if self::width(shown) == width {
+        shown.to_string()
+    } else {
+        format!("{:<width$}", "?", width = width)
+    };
+    Span::styled(shown, incorrect_char_style(theme))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input_mode::InputMode;
+
+    fn graphemes(text: &str) -> Vec<String> {
+        text.chars().map(String::from).collect()
+    }
+
+    fn wrapped(text: &str, width: u16) -> Vec<String> {
+        let g = graphemes(text);
+        wrap(&g, width, |_| 0).into_iter().map(|range| g[range].concat()).collect()
+    }
+
+    #[test]
+    fn wraps_after_spaces_and_splits_long_words() {
+        assert_eq!(wrapped("the quick brown fox", 10), ["the quick ", "brown fox"]);
+        assert_eq!(wrapped("a extraordinarily b", 8), ["a ", "extraord", "inarily ", "b"]);
+        assert_eq!(wrapped("", 10), [""]);
+    }
+
+    #[test]
+    fn keeps_the_cursor_line_in_view() {
+        let lines = [0..10, 10..20, 20..30, 30..40, 40..45];
+        assert_eq!(cursor_line(&lines, 0), 0);
+        assert_eq!(cursor_line(&lines, 10), 1);
+        assert_eq!(cursor_line(&lines, 45), 4);
+
+        // The line before the cursor's stays visible for context...
+        assert_eq!(viewport(0, 5, 3), 0..3);
+        assert_eq!(viewport(1, 5, 3), 0..3);
+        assert_eq!(viewport(2, 5, 3), 1..4);
+        // ...and the view stops at the last line
+        assert_eq!(viewport(4, 5, 3), 2..5);
+        assert_eq!(viewport(0, 2, 3), 0..2);
+    }
+
+    #[test]
+    fn shows_the_wrong_character_under_the_expected_one() {
+        let mut session = TypingSession::new("cat sat", InputMode::Normal);
+        for c in "cxt ".chars() {
+            session.type_char(c);
+        }
+        let all = wrap(session.target(), 20, |_| 0);
+        let lines = passage_lines(&session, &all, ErrorCue::TypedBelow, &Theme::default());
+        let text: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+        assert_eq!(text, ["cat sat", " x     "]);
+
+        let underlined = passage_lines(&session, &all, ErrorCue::Underline, &Theme::default());
+        assert_eq!(underlined.len(), 1);
+        assert!(underlined[0].spans[1].style.add_modifier.contains(Modifier::UNDERLINED));
+    }
+
+    #[test]
+    fn draws_extra_letters_after_the_word() {
+        let mut session = TypingSession::new("cat sat", InputMode::Normal);
+        for c in "catss".chars() {
+            session.type_char(c);
+        }
+        let g = session.target();
+        let lines = wrap(g, 6, |i| overflow_width(&session, i));
+        assert_eq!(lines, [0..4, 4..7]);
+
+        let text = passage_lines(&session, &lines, ErrorCue::TypedBelow, &Theme::default());
+        let text: Vec<String> = text.iter().map(|line| line.to_string()).collect();
+        assert_eq!(text, ["catss ", "      ", "sat", "   "]);
+    }
+}
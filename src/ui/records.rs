@@ -0,0 +1,108 @@
+use chrono::Local;
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::stats::{ProgressData, RecordKey};
+use crate::ui::dialogs::centered_rect;
+use crate::ui::theme::{subtitle_style, wpm_color, Theme};
+
+/// Render the personal bests overlay: the current record for every key, plus
+/// the record history for the key tests are taken under now.
+pub fn render_personal_bests(f: &mut Frame, progress: &ProgressData, current: &RecordKey, theme: &Theme) {
+    let area = centered_rect(70, 80, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD))
+        .title(" ★ Personal Bests ")
+        .title_style(Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD));
+
+    let header_style = subtitle_style(theme).add_modifier(Modifier::BOLD);
+    let mut best_lines = vec![Line::from(Span::styled(
+        format!("{:<20} {:<8} {:>7} {:>7}  {}", "Mode", "Length", "WPM", "Acc", "Set on"),
+        header_style,
+    ))];
+
+    let bests = progress.personal_bests();
+    if bests.is_empty() {
+        best_lines.push(Line::from(Span::styled("No records yet - finish a test!", subtitle_style(theme))));
+    }
+    for (key, result) in bests {
+        let is_current = key == *current;
+        let marker_style = if is_current {
+            Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.secondary())
+        };
+        best_lines.push(Line::from(vec![
+            Span::styled(format!("{:<20} {:<8} ", key.mode.name(), key.length.name()), marker_style),
+            Span::styled(
+                format!("{:>7.1}", result.wpm),
+                Style::default().fg(wpm_color(theme, result.wpm)).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(format!(" {:>6.1}%", result.accuracy), Style::default().fg(theme.correct())),
+            Span::styled(
+                format!("  {}", result.timestamp.with_timezone(&Local).format("%Y-%m-%d")),
+                subtitle_style(theme),
+            ),
+        ]));
+        best_lines.push(Line::from(Span::styled(format!("  {}", key.details()), subtitle_style(theme))));
+    }
+
+    let mut history_lines = vec![
+        Line::from(Span::styled(
+            format!("Record history - {} ({})", current.mode.name(), current.length.name()),
+            header_style,
+        )),
+        Line::from(Span::styled(format!("  {}", current.details()), subtitle_style(theme))),
+    ];
+    let history = progress.personal_best_history(current);
+    if history.is_empty() {
+        history_lines.push(Line::from(Span::styled("No records under these conditions yet", subtitle_style(theme))));
+    }
+    for pb in history.iter().rev() {
+        let gain = match pb.previous_wpm {
+            Some(prev) => format!("+{:.1}", pb.wpm - prev),
+            None => "first".to_string(),
+        };
+        history_lines.push(Line::from(vec![
+            Span::styled(
+                format!("{}  ", pb.timestamp.with_timezone(&Local).format("%Y-%m-%d %H:%M")),
+                subtitle_style(theme),
+            ),
+            Span::styled(
+                format!("{:>6.1} WPM", pb.wpm),
+                Style::default().fg(wpm_color(theme, pb.wpm)).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(format!("  {:>5.1}%", pb.accuracy), Style::default().fg(theme.correct())),
+            Span::styled(format!("  ({})", gain), Style::default().fg(theme.warning())),
+        ]));
+    }
+
+    let controls = Line::from(vec![
+        Span::styled("[Esc] ", Style::default().fg(theme.subtitle())),
+        Span::raw("Close"),
+    ]);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([
+            Constraint::Length(best_lines.len() as u16 + 1),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ])
+        .split(area);
+
+    f.render_widget(block, area);
+    f.render_widget(Paragraph::new(best_lines), chunks[0]);
+    f.render_widget(Paragraph::new(history_lines), chunks[1]);
+    f.render_widget(Paragraph::new(controls).alignment(Alignment::Center), chunks[2]);
+}
@@ -1,39 +1,171 @@
+//! Color themes.
+//!
+//! A theme is a TOML file giving a name and a `#rrggbb` color for every
+//! palette slot:
+//!
+//! ```toml
+//! name = "Gruvbox"
+//! primary = "#fbbd2e"
+//! secondary = "#d3869b"
+//! correct = "#b8bb26"
+//! error = "#fb4934"
+//! current_fg = "#282828"
+//! current_bg = "#fabd2f"
+//! pending = "#928374"
+//! subtitle = "#bdae93"
+//! warning = "#fe8019"
+//! good = "#fabd2f"
+//! ```
+//!
+//! `good` may be left out, for themes written before it was added.
+//!
+//! The built-in themes ship with the binary in this format. More can be
+//! added by dropping `<name>.toml` files into `~/.ratatap/themes/`; a file
+//! named like a built-in theme (e.g. `nord.toml`) replaces it. Themes read
+//! from a file are reloaded when the file changes.
+
 use ratatui::style::{Color, Modifier, Style};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
 use std::fs;
-use std::path::PathBuf;
-
-#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Serialize, serde::Deserialize)]
-pub enum Theme {
-    Synthwave,
-    Dracula,
-    OneDark,
-    Monokai,
-    Nord,
-    #[default]
-    Gruvbox,
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+const DEFAULT_THEME: &str = "gruvbox";
+
+const BUNDLED: &[(&str, &str)] = &[
+    ("synthwave", include_str!("../../assets/themes/synthwave.toml")),
+    ("dracula", include_str!("../../assets/themes/dracula.toml")),
+    ("one_dark", include_str!("../../assets/themes/one_dark.toml")),
+    ("monokai", include_str!("../../assets/themes/monokai.toml")),
+    ("nord", include_str!("../../assets/themes/nord.toml")),
+    ("gruvbox", include_str!("../../assets/themes/gruvbox.toml")),
+    ("high_contrast", include_str!("../../assets/themes/high_contrast.toml")),
+    ("deuteranopia", include_str!("../../assets/themes/deuteranopia.toml")),
+    ("protanopia", include_str!("../../assets/themes/protanopia.toml")),
+    ("tritanopia", include_str!("../../assets/themes/tritanopia.toml")),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// File stem, e.g. `one_dark`
+    key: String,
+    name: String,
+    palette: Palette,
+    /// File the theme was read from, for reloading
+    file: Option<Source>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Source {
+    path: PathBuf,
+    /// How the file looked when the colors were read
+    loaded: Option<Stamp>,
+    /// How it looked on the last check. Changes are only read once this
+    /// stops moving, so a save isn't read halfway through.
+    seen: Option<Stamp>,
+}
+
+/// Modification time and size of a theme file
+type Stamp = (SystemTime, u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+struct Palette {
+    /// Accent color (borders, highlights)
+    #[serde(deserialize_with = "hex")]
+    primary: Color,
+    #[serde(deserialize_with = "hex")]
+    secondary: Color,
+    #[serde(deserialize_with = "hex")]
+    correct: Color,
+    #[serde(deserialize_with = "hex")]
+    error: Color,
+    #[serde(deserialize_with = "hex")]
+    current_fg: Color,
+    #[serde(deserialize_with = "hex")]
+    current_bg: Color,
+    #[serde(deserialize_with = "hex")]
+    pending: Color,
+    #[serde(deserialize_with = "hex")]
+    subtitle: Color,
+    #[serde(deserialize_with = "hex")]
+    warning: Color,
+    /// Typing speeds between the correct and warning bands
+    #[serde(default, deserialize_with = "optional_hex")]
+    good: Option<Color>,
+}
+
+#[derive(Deserialize)]
+struct ThemeFile {
+    /// Defaults to the file name
+    name: Option<String>,
+    #[serde(flatten)]
+    palette: Palette,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let (key, data) = BUNDLED
+            .iter()
+            .find(|(key, _)| *key == DEFAULT_THEME)
+            .expect("default theme is bundled");
+        parse(key, data, None).expect("bundled themes are valid")
+    }
 }
 
 impl Theme {
+    /// The theme after this one in the Ctrl+T cycle
     pub fn next(&self) -> Theme {
-        match self {
-            Theme::Synthwave => Theme::Dracula,
-            Theme::Dracula => Theme::OneDark,
-            Theme::OneDark => Theme::Monokai,
-            Theme::Monokai => Theme::Nord,
-            Theme::Nord => Theme::Gruvbox,
-            Theme::Gruvbox => Theme::Synthwave,
-        }
+        let all = Theme::all();
+        let position = all.iter().position(|t| t.key == self.key);
+        let next = position.map_or(0, |i| (i + 1) % all.len());
+        all[next].clone()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
     }
 
-    pub fn name(&self) -> &'static str {
-        match self {
-            Theme::Synthwave => "Synthwave",
-            Theme::Dracula => "Dracula",
-            Theme::OneDark => "One Dark",
-            Theme::Monokai => "Monokai",
-            Theme::Nord => "Nord",
-            Theme::Gruvbox => "Gruvbox",
+    /// Built-in themes in their usual order, then user themes by file name.
+    /// User files that fail to parse are left out.
+    pub fn all() -> Vec<Theme> {
+        let mut themes: Vec<Theme> = BUNDLED
+            .iter()
+            .map(|(key, data)| parse(key, data, None).expect("bundled themes are valid"))
+            .collect();
+
+        let mut paths: Vec<PathBuf> = match fs::read_dir(user_dir()) {
+            Ok(entries) => entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|path| path.extension().is_some_and(|e| e.eq_ignore_ascii_case("toml")))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        paths.sort();
+
+        for theme in paths.iter().filter_map(|path| read_file(path).ok()) {
+            match themes.iter_mut().find(|t| t.key == theme.key) {
+                Some(bundled) => *bundled = theme,
+                None => themes.push(theme),
+            }
         }
+        themes
+    }
+
+    /// Look up a theme by name or file name, ignoring case, spaces, dashes
+    /// and underscores
+    pub fn from_name(name: &str) -> Option<Theme> {
+        let normalize = |s: &str| {
+            s.chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+                .to_lowercase()
+        };
+        let wanted = normalize(name);
+        Theme::all()
+            .into_iter()
+            .find(|t| normalize(&t.name) == wanted || normalize(&t.key) == wanted)
     }
 
     fn get_path() -> PathBuf {
@@ -43,14 +175,16 @@ impl Theme {
         path
     }
 
+    /// The saved theme. `theme.json` holds the theme's name, which is how
+    /// the built-in themes were always stored.
     pub fn load() -> Self {
         let path = Self::get_path();
-        if path.exists() {
-            if let Ok(data) = fs::read_to_string(&path) {
-                if let Ok(theme) = serde_json::from_str(&data) {
-                    return theme;
-                }
-            }
+        if path.exists()
+            && let Ok(data) = fs::read_to_string(&path)
+            && let Ok(name) = serde_json::from_str::<String>(&data)
+            && let Some(theme) = Theme::from_name(&name)
+        {
+            return theme;
         }
         Theme::default()
     }
@@ -60,118 +194,136 @@ impl Theme {
         if let Some(parent) = path.parent() {
             let _ = fs::create_dir_all(parent);
         }
-        if let Ok(data) = serde_json::to_string(self) {
+        if let Ok(data) = serde_json::to_string(&self.name) {
             let _ = fs::write(path, data);
         }
     }
 
+    /// Re-read the theme if its file changed since it was loaded, once the
+    /// change has settled between two calls. Returns whether it was
+    /// reloaded; on an error the current colors are kept and the same change
+    /// isn't reported again.
+    pub fn reload(&mut self) -> Result<bool, String> {
+        let Some(source) = &mut self.file else {
+            return Ok(false);
+        };
+        let current = stamp(&source.path);
+        let settled = current == source.seen;
+        source.seen = current;
+        if current == source.loaded || !settled {
+            return Ok(false);
+        }
+        source.loaded = current;
+        let path = source.path.clone();
+        *self = read_file(&path)?;
+        Ok(true)
+    }
+
     /// Primary accent color (borders, highlights)
     pub fn primary(&self) -> Color {
-        match self {
-            Theme::Synthwave => Color::Rgb(57, 255, 250),   // Neon cyan
-            Theme::Dracula => Color::Rgb(189, 147, 249),    // Purple
-            Theme::OneDark => Color::Rgb(97, 175, 239),     // Blue
-            Theme::Monokai => Color::Rgb(102, 217, 239),    // Cyan
-            Theme::Nord => Color::Rgb(136, 192, 208),       // Frost blue
-            Theme::Gruvbox => Color::Rgb(251, 189, 46),     // Yellow
-        }
+        self.palette.primary
     }
 
     /// Secondary accent color
     pub fn secondary(&self) -> Color {
-        match self {
-            Theme::Synthwave => Color::Rgb(191, 64, 255),   // Neon purple
-            Theme::Dracula => Color::Rgb(255, 121, 198),    // Pink
-            Theme::OneDark => Color::Rgb(198, 120, 221),    // Purple
-            Theme::Monokai => Color::Rgb(174, 129, 255),    // Purple
-            Theme::Nord => Color::Rgb(180, 142, 173),       // Purple
-            Theme::Gruvbox => Color::Rgb(211, 134, 155),    // Purple
-        }
+        self.palette.secondary
     }
 
     /// Correct text color (green-ish)
     pub fn correct(&self) -> Color {
-        match self {
-            Theme::Synthwave => Color::Rgb(57, 255, 20),    // Neon green
-            Theme::Dracula => Color::Rgb(80, 250, 123),     // Green
-            Theme::OneDark => Color::Rgb(152, 195, 121),    // Green
-            Theme::Monokai => Color::Rgb(166, 226, 46),     // Green
-            Theme::Nord => Color::Rgb(163, 190, 140),       // Green
-            Theme::Gruvbox => Color::Rgb(184, 187, 38),     // Green
-        }
+        self.palette.correct
     }
 
     /// Error/incorrect text color (red/pink-ish)
     pub fn error(&self) -> Color {
-        match self {
-            Theme::Synthwave => Color::Rgb(255, 105, 180),  // Hot pink
-            Theme::Dracula => Color::Rgb(255, 85, 85),      // Red
-            Theme::OneDark => Color::Rgb(224, 108, 117),    // Red
-            Theme::Monokai => Color::Rgb(249, 38, 114),     // Pink
-            Theme::Nord => Color::Rgb(191, 97, 106),        // Red
-            Theme::Gruvbox => Color::Rgb(251, 73, 52),      // Red
-        }
+        self.palette.error
     }
 
     /// Current character foreground
     pub fn current_fg(&self) -> Color {
-        match self {
-            Theme::Synthwave => Color::Rgb(20, 20, 30),     // Dark
-            Theme::Dracula => Color::Rgb(40, 42, 54),       // Background
-            Theme::OneDark => Color::Rgb(40, 44, 52),       // Background
-            Theme::Monokai => Color::Rgb(39, 40, 34),       // Background
-            Theme::Nord => Color::Rgb(46, 52, 64),          // Background
-            Theme::Gruvbox => Color::Rgb(40, 40, 40),       // Background
-        }
+        self.palette.current_fg
     }
 
     /// Current character background (high visibility)
     pub fn current_bg(&self) -> Color {
-        match self {
-            Theme::Synthwave => Color::Rgb(255, 255, 100),  // Bright yellow
-            Theme::Dracula => Color::Rgb(241, 250, 140),    // Yellow
-            Theme::OneDark => Color::Rgb(229, 192, 123),    // Yellow
-            Theme::Monokai => Color::Rgb(230, 219, 116),    // Yellow
-            Theme::Nord => Color::Rgb(235, 203, 139),       // Yellow
-            Theme::Gruvbox => Color::Rgb(250, 189, 47),     // Yellow
-        }
+        self.palette.current_bg
     }
 
     /// Pending/untyped text color (dimmed)
     pub fn pending(&self) -> Color {
-        match self {
-            Theme::Synthwave => Color::Rgb(100, 100, 140),
-            Theme::Dracula => Color::Rgb(98, 114, 164),     // Comment
-            Theme::OneDark => Color::Rgb(92, 99, 112),      // Comment
-            Theme::Monokai => Color::Rgb(117, 113, 94),     // Comment
-            Theme::Nord => Color::Rgb(76, 86, 106),         // Comment
-            Theme::Gruvbox => Color::Rgb(146, 131, 116),    // Gray
-        }
+        self.palette.pending
     }
 
     /// Subtitle/label color
     pub fn subtitle(&self) -> Color {
-        match self {
-            Theme::Synthwave => Color::Rgb(180, 180, 220),
-            Theme::Dracula => Color::Rgb(139, 148, 158),
-            Theme::OneDark => Color::Rgb(171, 178, 191),
-            Theme::Monokai => Color::Rgb(166, 172, 163),
-            Theme::Nord => Color::Rgb(216, 222, 233),
-            Theme::Gruvbox => Color::Rgb(189, 174, 147),
-        }
+        self.palette.subtitle
     }
 
     /// Warning color (orange/yellow)
     pub fn warning(&self) -> Color {
-        match self {
-            Theme::Synthwave => Color::Rgb(255, 176, 0),
-            Theme::Dracula => Color::Rgb(255, 184, 108),    // Orange
-            Theme::OneDark => Color::Rgb(209, 154, 102),    // Orange
-            Theme::Monokai => Color::Rgb(253, 151, 31),     // Orange
-            Theme::Nord => Color::Rgb(208, 135, 112),       // Orange
-            Theme::Gruvbox => Color::Rgb(254, 128, 25),     // Orange
-        }
+        self.palette.warning
+    }
+
+    /// Good but not top speed (yellow-ish); halfway between the correct and
+    /// warning colors when the theme leaves it out
+    pub fn good(&self) -> Color {
+        self.palette
+            .good
+            .unwrap_or_else(|| interpolate_color(self.palette.correct, self.palette.warning, 0.5))
+    }
+}
+
+fn user_dir() -> PathBuf {
+    let mut path = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push(".ratatap");
+    path.push("themes");
+    path
+}
+
+fn read_file(path: &Path) -> Result<Theme, String> {
+    let key = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let stamp = stamp(path);
+    let data = fs::read_to_string(path).map_err(|e| format!("could not read '{}': {}", file_name, e))?;
+    let source = Source { path: path.to_path_buf(), loaded: stamp, seen: stamp };
+    parse(&key, &data, Some(source)).map_err(|e| format!("could not parse '{}': {}", file_name, e))
+}
+
+fn stamp(path: &Path) -> Option<Stamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn parse(key: &str, data: &str, file: Option<Source>) -> Result<Theme, String> {
+    let theme: ThemeFile = toml::from_str(data).map_err(|e| e.message().to_string())?;
+    Ok(Theme {
+        key: key.to_string(),
+        name: theme.name.unwrap_or_else(|| crate::language::display_name(key)),
+        palette: theme.palette,
+        file,
+    })
+}
+
+/// Parse `#rrggbb` (the `#` is optional)
+pub fn parse_hex(value: &str) -> Option<Color> {
+    let digits = value.strip_prefix('#').unwrap_or(value);
+    if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
     }
+    let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).ok();
+    Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+fn hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    parse_hex(&value).ok_or_else(|| D::Error::custom(format!("invalid color '{}' (expected #rrggbb)", value)))
+}
+
+fn optional_hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Color>, D::Error> {
+    hex(deserializer).map(Some)
 }
 
 // Style helper functions that take a theme parameter
@@ -211,7 +363,7 @@ pub fn wpm_color(theme: &Theme, wpm: f64) -> Color {
     if wpm >= 60.0 {
         theme.correct()
     } else if wpm >= 40.0 {
-        Color::Rgb(200, 255, 100)
+        theme.good()
     } else if wpm >= 20.0 {
         theme.warning()
     } else {
@@ -242,23 +394,105 @@ pub fn current_key_style(theme: &Theme) -> Style {
         .add_modifier(Modifier::BOLD)
 }
 
-/// Get color for a key based on its error count
-pub fn key_error_color(theme: &Theme, error_count: usize) -> Color {
-    if error_count <= 3 {
-        theme.correct()         // 0-3 errors: good
-    } else if error_count <= 8 {
-        interpolate_color(theme.correct(), theme.warning(), 0.5)
-    } else if error_count <= 15 {
-        theme.warning()         // 9-15: needs practice
-    } else if error_count <= 25 {
-        interpolate_color(theme.warning(), theme.error(), 0.5)
-    } else {
-        theme.error()           // 25+: major weakness
-    }
-}
-
 /// Style for rendering a progress bar
 pub fn progress_bar_style(theme: &Theme, ratio: f64) -> Style {
     let color = interpolate_color(theme.error(), theme.correct(), ratio);
     Style::default().fg(color)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const PALETTE: &str = r##"
+        primary = "#010203"
+        secondary = "#000000"
+        correct = "#000000"
+        error = "#000000"
+        current_fg = "#000000"
+        current_bg = "#000000"
+        pending = "#000000"
+        subtitle = "#000000"
+        warning = "#000000"
+    "##;
+
+    #[test]
+    fn bundled_themes_parse_in_order() {
+        let names: Vec<String> = BUNDLED
+            .iter()
+            .map(|(key, data)| parse(key, data, None).unwrap().name)
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "Synthwave",
+                "Dracula",
+                "One Dark",
+                "Monokai",
+                "Nord",
+                "Gruvbox",
+                "High Contrast",
+                "Deuteranopia",
+                "Protanopia",
+                "Tritanopia"
+            ]
+        );
+        assert_eq!(Theme::default().primary(), Color::Rgb(251, 189, 46));
+    }
+
+    #[test]
+    fn parses_names_and_rejects_bad_colors() {
+        let theme = parse("my_theme", PALETTE, None).unwrap();
+        assert_eq!(theme.name(), "My theme");
+        assert_eq!(theme.primary(), Color::Rgb(1, 2, 3));
+
+        let bad = PALETTE.replace("#010203", "#01020g");
+        assert!(parse("bad", &bad, None).unwrap_err().contains("invalid color '#01020g'"));
+        let missing = PALETTE.replace("warning", "warn");
+        assert!(parse("missing", &missing, None).unwrap_err().contains("warning"));
+    }
+
+    #[test]
+    fn good_speeds_use_the_palette() {
+        let theme = parse("my_theme", &format!("{}good = \"#0a0b0c\"", PALETTE), None).unwrap();
+        assert_eq!(wpm_color(&theme, 50.0), Color::Rgb(10, 11, 12));
+        // Older themes without the slot get a blend of correct and warning
+        let older = PALETTE
+            .replace(r##"correct = "#000000""##, r##"correct = "#00c800""##)
+            .replace(r##"warning = "#000000""##, r##"warning = "#c86400""##);
+        assert_eq!(wpm_color(&parse("older", &older, None).unwrap(), 50.0), Color::Rgb(100, 150, 0));
+        for (key, data) in BUNDLED {
+            assert!(parse(key, data, None).unwrap().palette.good.is_some(), "{}", key);
+        }
+    }
+
+    #[test]
+    fn reloads_when_the_file_changes() {
+        let path = std::env::temp_dir().join(format!("ratatap-theme-{}.toml", std::process::id()));
+        fs::write(&path, PALETTE).unwrap();
+        let mut theme = read_file(&path).unwrap();
+        assert_eq!(theme.reload(), Ok(false));
+
+        let bump = |path: &Path, secs| {
+            let file = fs::File::options().write(true).open(path).unwrap();
+            file.set_modified(SystemTime::now() + Duration::from_secs(secs)).unwrap();
+        };
+        fs::write(&path, PALETTE.replace("#010203", "#ffffff")).unwrap();
+        bump(&path, 10);
+        // Read on the second look, once the file has stopped changing
+        assert_eq!(theme.reload(), Ok(false));
+        assert_eq!(theme.reload(), Ok(true));
+        assert_eq!(theme.primary(), Color::Rgb(255, 255, 255));
+
+        // A broken edit keeps the last good colors and is reported once
+        fs::write(&path, "primary = 1").unwrap();
+        bump(&path, 20);
+        assert_eq!(theme.reload(), Ok(false));
+        assert!(theme.reload().is_err());
+        assert_eq!(theme.reload(), Ok(false));
+        assert_eq!(theme.primary(), Color::Rgb(255, 255, 255));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,74 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use std::time::{Duration, Instant};
+
+use crate::ui::theme::{subtitle_style, Theme};
+
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+const MAX_VISIBLE: usize = 3;
+
+/// A short-lived notification shown in the top-right corner
+pub struct Toast {
+    pub title: String,
+    pub message: String,
+    /// Set when the toast is first drawn, so queued toasts wait their turn
+    expires_at: Option<Instant>,
+}
+
+impl Toast {
+    pub fn new(title: impl Into<String>, message: impl Into<String>) -> Self {
+        Toast {
+            title: title.into(),
+            message: message.into(),
+            expires_at: None,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|expires_at| Instant::now() >= expires_at)
+    }
+}
+
+pub fn render_toasts(f: &mut Frame, toasts: &mut [Toast], theme: &Theme) {
+    let queued = toasts.len();
+    let area = f.area();
+    let width = 44.min(area.width);
+    let height = 4;
+
+    for (i, toast) in toasts.iter_mut().take(MAX_VISIBLE).enumerate() {
+        let y = area.y + 1 + i as u16 * height;
+        if y + height > area.bottom() {
+            break;
+        }
+        let rect = Rect::new(area.right().saturating_sub(width + 1), y, width, height);
+        toast.expires_at.get_or_insert_with(|| Instant::now() + TOAST_DURATION);
+
+        let mut lines = vec![Line::from(Span::styled(
+            toast.message.clone(),
+            subtitle_style(theme),
+        ))];
+        if i + 1 == MAX_VISIBLE && queued > MAX_VISIBLE {
+            lines.push(Line::from(Span::styled(
+                format!("+{} more", queued - MAX_VISIBLE),
+                Style::default().fg(theme.secondary()),
+            )));
+        }
+
+        let toast_widget = Paragraph::new(lines).wrap(Wrap { trim: true }).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD))
+                .title(format!(" {} ", toast.title))
+                .title_style(Style::default().fg(theme.warning()).add_modifier(Modifier::BOLD)),
+        );
+
+        f.render_widget(Clear, rect);
+        f.render_widget(toast_widget, rect);
+    }
+}
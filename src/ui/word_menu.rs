@@ -0,0 +1,163 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::language::Language;
+use crate::modes::words::WordOptions;
+use crate::ui::dialogs::centered_rect;
+use crate::ui::theme::{subtitle_style, Theme};
+
+const ROWS: usize = 5;
+const MIN_LETTERS: usize = 1;
+const MAX_LETTERS: usize = 15;
+
+/// State for the in-app word options dialog
+pub struct WordMenu {
+    selected: usize,
+}
+
+impl WordMenu {
+    pub fn new() -> Self {
+        WordMenu { selected: 0 }
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % ROWS;
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = (self.selected + ROWS - 1) % ROWS;
+    }
+
+    /// Change the value of the selected row
+    pub fn cycle(&self, options: &mut WordOptions, forward: bool) {
+        match self.selected {
+            0 => {
+                options.difficulty = if forward {
+                    options.difficulty.next()
+                } else {
+                    options.difficulty.previous()
+                }
+            }
+            1 => options.avoid_repeats = !options.avoid_repeats,
+            2 => {
+                options.min_length = cycle_length(options.min_length, forward);
+                if let (Some(min), Some(max)) = (options.min_length, options.max_length)
+                    && min > max
+                {
+                    options.max_length = Some(min);
+                }
+            }
+            3 => {
+                options.max_length = cycle_length(options.max_length, forward);
+                if let (Some(min), Some(max)) = (options.min_length, options.max_length)
+                    && max < min
+                {
+                    options.min_length = Some(max);
+                }
+            }
+            _ => options.pseudo_words = !options.pseudo_words,
+        }
+    }
+}
+
+/// Step through "Any", 1, 2, ... 15 letters, wrapping at either end
+fn cycle_length(length: Option<usize>, forward: bool) -> Option<usize> {
+    match (length, forward) {
+        (None, true) => Some(MIN_LETTERS),
+        (None, false) => Some(MAX_LETTERS),
+        (Some(MAX_LETTERS), true) | (Some(MIN_LETTERS), false) => None,
+        (Some(n), true) => Some(n + 1),
+        (Some(n), false) => Some(n - 1),
+    }
+}
+
+fn length_name(length: Option<usize>) -> String {
+    match length {
+        Some(n) => format!("{} letters", n),
+        None => "Any".to_string(),
+    }
+}
+
+pub fn render_word_menu(f: &mut Frame, menu: &WordMenu, options: &WordOptions, language: &Language, theme: &Theme) {
+    let area = centered_rect(60, 50, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD))
+        .title(" Word Options ")
+        .title_style(Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD));
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([Constraint::Length(6), Constraint::Min(2), Constraint::Length(1)])
+        .split(area);
+
+    let repeats = if options.avoid_repeats { "Avoided" } else { "Allowed" };
+    let made_up = if options.pseudo_words { "Mixed in" } else { "Off" };
+    let available = options.difficulty.available(language);
+    let tier = if available {
+        options.difficulty.name().to_string()
+    } else {
+        format!("{} (whole list)", options.difficulty.name())
+    };
+    let rows = [
+        ("Words", tier),
+        ("Repeats", repeats.to_string()),
+        ("Shortest", length_name(options.min_length)),
+        ("Longest", length_name(options.max_length)),
+        ("Made-up", made_up.to_string()),
+    ];
+
+    let option_lines: Vec<Line> = rows
+        .iter()
+        .enumerate()
+        .map(|(i, (label, value))| {
+            let selected = i == menu.selected;
+            let marker = if selected { "▶ " } else { "  " };
+            let value_style = if selected {
+                Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.secondary())
+            };
+            Line::from(vec![
+                Span::styled(marker, Style::default().fg(theme.primary())),
+                Span::styled(format!("{:<10}", label), subtitle_style(theme)),
+                Span::styled(format!("◀ {} ▶", value), value_style),
+            ])
+        })
+        .collect();
+
+    let hint = if available {
+        "Common words come up more often, as in real text".to_string()
+    } else {
+        format!(
+            "{} is only ranked to {} words, so the {} draws from the whole list",
+            language.display_name(),
+            language.ranked,
+            options.difficulty.name().to_lowercase()
+        )
+    };
+    let hint = Line::from(Span::styled(hint, subtitle_style(theme)));
+
+    let controls = Line::from(vec![
+        Span::styled("↑↓ ", Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD)),
+        Span::raw("Select  "),
+        Span::styled("←→ ", Style::default().fg(theme.primary()).add_modifier(Modifier::BOLD)),
+        Span::raw("Change  "),
+        Span::styled("[Esc] ", Style::default().fg(theme.subtitle())),
+        Span::raw("Close"),
+    ]);
+
+    f.render_widget(block, area);
+    f.render_widget(Paragraph::new(option_lines), chunks[0]);
+    f.render_widget(Paragraph::new(hint).alignment(Alignment::Center).wrap(Wrap { trim: true }), chunks[1]);
+    f.render_widget(Paragraph::new(controls).alignment(Alignment::Center), chunks[2]);
+}